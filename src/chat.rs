@@ -0,0 +1,244 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use regex::{Regex, RegexBuilder};
+use serenity::{
+    client::Context,
+    model::prelude::{ChannelId, MessageId},
+};
+
+use crate::{
+    messages,
+    structs::{
+        config::{AlertRule, ServerConfig},
+        snapshot::{ChatMessage, Snapshot},
+    },
+};
+
+#[derive(Default)]
+pub struct ChatState {
+    /// chat lines of the last snapshot, the api returns overlapping lists
+    seen: HashSet<ChatMessage>,
+    started: bool,
+    /// last alert of every rule, by pattern
+    alerted: HashMap<String, Instant>,
+    /// compiled alert patterns, by pattern
+    patterns: HashMap<String, Regex>,
+}
+
+/// chat lines that weren't in the previous snapshot, oldest first
+pub fn new_messages<'a>(snapshot: &'a Snapshot, state: &mut ChatState) -> Vec<&'a ChatMessage> {
+    // the chat is missing if the seeder data is too old, keep what was seen before
+    if snapshot.chat.is_empty() {
+        return vec![];
+    }
+
+    let mut messages: Vec<&ChatMessage> = snapshot
+        .chat
+        .iter()
+        .filter(|message| !state.seen.contains(*message))
+        .collect();
+    messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    // don't repost the chat that was already there when the bot started
+    if !state.started {
+        messages.clear();
+        state.started = true;
+    }
+    state.seen = snapshot.chat.iter().cloned().collect();
+    messages
+}
+
+/// name of the team the player is in, if they're still on the server
+pub fn sender_team<'a>(snapshot: &'a Snapshot, sender: &str) -> Option<&'a str> {
+    snapshot
+        .teams
+        .iter()
+        .find(|team| {
+            team.players
+                .iter()
+                .any(|player| player.name.eq_ignore_ascii_case(sender))
+        })
+        .map(|team| team.name.as_str())
+}
+
+/// chat timestamps are shown as time only if they can be parsed
+fn format_time(timestamp: &str) -> String {
+    match chrono::DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => time.format("%T").to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+pub fn format_line(snapshot: &Snapshot, message: &ChatMessage) -> String {
+    let sender = match sender_team(snapshot, &message.sender) {
+        Some(team) => format!("[{}] {}", team, message.sender),
+        None => message.sender.clone(),
+    };
+    format!(
+        "`{}` **{}**: {}",
+        format_time(&message.timestamp),
+        sender.replace('*', "\\*"),
+        message.content.replace('`', "'")
+    )
+}
+
+/// post the new chat lines, batched in as few messages as possible
+pub async fn relay(ctx: &Context, channel: u64, snapshot: &Snapshot, new_chat: &[&ChatMessage]) {
+    let lines: Vec<String> = new_chat
+        .iter()
+        .map(|message| format_line(snapshot, message))
+        .collect();
+    messages::post_batched(ctx, channel, &lines).await;
+}
+
+/// patterns match case-insensitive, so plain keywords work as well
+fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+/// check the rules so the config fails to load instead of the alerts
+pub fn validate_rules(rules: &[AlertRule]) -> anyhow::Result<()> {
+    for rule in rules {
+        if let Err(e) = compile(&rule.pattern) {
+            anyhow::bail!("invalid chat alert pattern \"{}\": {}", rule.pattern, e);
+        }
+    }
+    Ok(())
+}
+
+/// ping the moderators for new chat lines that match a rule that isn't on cooldown
+pub async fn alert(
+    ctx: &Context,
+    server: &ServerConfig,
+    snapshot: &Snapshot,
+    messages: &[&ChatMessage],
+    state: &mut ChatState,
+) {
+    let alerts = match &server.chat_alerts {
+        Some(alerts) => alerts,
+        None => return,
+    };
+
+    for rule in &alerts.rules {
+        if !state.patterns.contains_key(&rule.pattern) {
+            match compile(&rule.pattern) {
+                Ok(pattern) => {
+                    state.patterns.insert(rule.pattern.clone(), pattern);
+                }
+                Err(e) => {
+                    log::error!("Invalid chat alert pattern \"{}\": {}", rule.pattern, e);
+                    continue;
+                }
+            }
+        }
+        let pattern = &state.patterns[&rule.pattern];
+
+        for message in messages {
+            if !pattern.is_match(&message.content) {
+                continue;
+            }
+            if let Some(alerted) = state.alerted.get(&rule.pattern) {
+                if alerted.elapsed() < Duration::from_secs(rule.cooldown) {
+                    break;
+                }
+            }
+            state.alerted.insert(rule.pattern.clone(), Instant::now());
+
+            let mut content = format!(
+                "<@&{}> `{}` on {}\n{}",
+                alerts.role,
+                rule.pattern,
+                server.server_name,
+                format_line(snapshot, message)
+            );
+            if let Some(message_id) = server.messages.first() {
+                let link = MessageId(*message_id)
+                    .link_ensured(ctx, ChannelId(server.channel), None)
+                    .await;
+                content.push_str(&format!("\n[Scoreboard]({})", link));
+            }
+
+            if let Err(e) = ChannelId(alerts.channel)
+                .send_message(&ctx.http, |m| {
+                    m.content(content)
+                        .allowed_mentions(|mentions| mentions.roles(vec![alerts.role]))
+                })
+                .await
+            {
+                log::error!("Failed to send chat alert: {:#?}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(timestamp: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            timestamp: timestamp.into(),
+            sender: "player".into(),
+            content: content.into(),
+        }
+    }
+
+    fn with_chat(chat: Vec<ChatMessage>) -> Snapshot {
+        let mut snapshot = Snapshot::empty("Amiens", "Conquest");
+        snapshot.chat = chat;
+        snapshot
+    }
+
+    fn contents(messages: Vec<&ChatMessage>) -> Vec<&str> {
+        messages
+            .into_iter()
+            .map(|message| message.content.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn skips_the_chat_from_before_the_start() {
+        let mut state = ChatState::default();
+        // no chat yet doesn't count as the start
+        assert!(new_messages(&with_chat(vec![]), &mut state).is_empty());
+
+        let first = with_chat(vec![message("2023-01-01T10:00:00Z", "old")]);
+        assert!(new_messages(&first, &mut state).is_empty());
+
+        let second = with_chat(vec![
+            message("2023-01-01T10:00:00Z", "old"),
+            message("2023-01-01T10:00:05Z", "new"),
+        ]);
+        assert_eq!(contents(new_messages(&second, &mut state)), vec!["new"]);
+    }
+
+    #[test]
+    fn returns_overlapping_chat_once_oldest_first() {
+        let mut state = ChatState::default();
+        new_messages(
+            &with_chat(vec![message("2023-01-01T10:00:00Z", "a")]),
+            &mut state,
+        );
+
+        let next = with_chat(vec![
+            message("2023-01-01T10:00:20Z", "c"),
+            message("2023-01-01T10:00:00Z", "a"),
+            message("2023-01-01T10:00:10Z", "b"),
+        ]);
+        assert_eq!(contents(new_messages(&next, &mut state)), vec!["b", "c"]);
+        assert!(new_messages(&next, &mut state).is_empty());
+    }
+
+    #[test]
+    fn missing_chat_keeps_what_was_seen() {
+        let mut state = ChatState::default();
+        let chat = with_chat(vec![message("2023-01-01T10:00:00Z", "a")]);
+        new_messages(&chat, &mut state);
+
+        assert!(new_messages(&with_chat(vec![]), &mut state).is_empty());
+        assert!(new_messages(&chat, &mut state).is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+use serenity::{
+    client::Context,
+    model::application::{
+        command::Command,
+        interaction::{
+            application_command::ApplicationCommandInteraction, InteractionResponseType,
+        },
+    },
+};
+
+use crate::structs::{config::SharedConfig, snapshot::SnapshotCache};
+pub mod player;
+pub mod playerlist;
+pub mod scoreboard;
+
+pub async fn register(ctx: &Context) -> serenity::Result<Vec<Command>> {
+    Command::set_global_application_commands(&ctx.http, |commands| {
+        commands
+            .create_application_command(|command| player::register(command))
+            .create_application_command(|command| scoreboard::register(command))
+            .create_application_command(|command| playerlist::register(command))
+    })
+    .await
+}
+
+pub async fn handle(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    config: &SharedConfig,
+    snapshots: &SnapshotCache,
+) {
+    // answer right away, discord gives up on interactions after 3 seconds
+    if let Err(e) = command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await
+    {
+        log::error!("Failed to respond to /{}: {:#?}", command.data.name, e);
+        return;
+    }
+
+    let contents = match command.data.name.as_str() {
+        "player" => vec![player::run(&command.data.options, snapshots).await],
+        "scoreboard" => scoreboard::run(&command.data.options, snapshots).await,
+        "playerlist" => vec![playerlist::run(command, config).await],
+        _ => vec!["Unknown command".into()],
+    };
+
+    let mut contents = contents.into_iter();
+    let first = contents.next().unwrap_or_default();
+    if let Err(e) = command
+        .edit_original_interaction_response(&ctx.http, |response| response.content(first))
+        .await
+    {
+        log::error!("Failed to respond to /{}: {:#?}", command.data.name, e);
+        return;
+    }
+
+    // responses are limited to one message, the rest is sent as followups
+    for content in contents {
+        if let Err(e) = command
+            .create_followup_message(&ctx.http, |message| {
+                message.ephemeral(true).content(content)
+            })
+            .await
+        {
+            log::error!(
+                "Failed to send followup of /{}: {:#?}",
+                command.data.name,
+                e
+            );
+            return;
+        }
+    }
+}
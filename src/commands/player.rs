@@ -0,0 +1,111 @@
+use serenity::{
+    builder::CreateApplicationCommand,
+    model::application::{
+        command::CommandOptionType, interaction::application_command::CommandDataOption,
+    },
+};
+
+use crate::{
+    structs::snapshot::{Player, SnapshotCache},
+    to_table,
+};
+
+/// players shown if the name matches more than one
+const MAX_MATCHES: usize = 10;
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("player")
+        .description("Look up a player on the followed servers")
+        .create_option(|option| {
+            option
+                .name("name")
+                .description("(part of) the name of the player")
+                .kind(CommandOptionType::String)
+                .required(true)
+        })
+}
+
+pub async fn run(options: &[CommandDataOption], snapshots: &SnapshotCache) -> String {
+    let name = match options
+        .iter()
+        .find(|option| option.name == "name")
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+    {
+        Some(name) => name,
+        None => return "No player name given".into(),
+    };
+    let search = name.to_lowercase();
+
+    let snapshots = snapshots.read().await;
+    let mut matches = vec![];
+    for (server_name, snapshot) in snapshots.iter() {
+        for team in &snapshot.teams {
+            for player in &team.players {
+                if player.name.to_lowercase().contains(&search) {
+                    matches.push((server_name, &team.name, player));
+                }
+            }
+        }
+    }
+    // an exact match wins over players that only contain the name
+    if let Some(exact) = matches
+        .iter()
+        .find(|(_, _, player)| player.name.to_lowercase() == search)
+    {
+        matches = vec![*exact];
+    }
+
+    match matches.len() {
+        0 => format!("No player found matching \"{}\"", name),
+        1 => {
+            let (server_name, team_name, player) = matches[0];
+            player_info(server_name, team_name, player)
+        }
+        amount => {
+            let mut lines = vec![format!("{} players found, be more specific:", amount)];
+            for (server_name, team_name, player) in matches.iter().take(MAX_MATCHES) {
+                lines.push(format!(
+                    "- {} ({} on {})",
+                    player.display_name(),
+                    team_name,
+                    server_name
+                ));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+fn player_info(server_name: &str, team_name: &str, player: &Player) -> String {
+    let mut lines = vec![
+        format!("**{}** on {}", player.display_name(), server_name),
+        format!("Team: {}", team_name),
+        format!("Rank: {}", player.rank),
+    ];
+    if let Some(squad) = &player.squad {
+        lines.push(format!("Squad: {}", squad));
+    }
+    if let Some(class) = player.class.as_ref().filter(|class| !class.is_empty()) {
+        lines.push(format!("Class: {}", class));
+    }
+    if let Some(score) = player.score {
+        lines.push(format!("Score: {}", score));
+    }
+    if let (Some(kills), Some(deaths)) = (player.kills, player.deaths) {
+        lines.push(format!(
+            "K/D: {}/{} ({:.2})",
+            kills,
+            deaths,
+            kills as f64 / deaths.max(1) as f64
+        ));
+    }
+    if let Some(ping) = player.ping {
+        lines.push(format!("Ping: {}ms", ping));
+    }
+    if let Some(join_time) = player.join_time {
+        lines.push(format!("Time on server: {}", to_table::playtime(join_time)));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,224 @@
+use serenity::{
+    builder::{CreateApplicationCommand, CreateApplicationCommandOption},
+    model::{
+        application::{
+            command::CommandOptionType,
+            interaction::application_command::{ApplicationCommandInteraction, CommandDataOption},
+        },
+        Permissions,
+    },
+};
+
+use crate::structs::config::{Games, ServerConfig, SharedConfig, CONFIG_PATH};
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("playerlist")
+        .description("Change the settings of the playerlist bot")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("set-server")
+                .description("Follow another battlefield server")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option
+                        .name("name")
+                        .description("Name of the battlefield server to follow")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|option| {
+                    option
+                        .name("server_id")
+                        .description("gameId to look the server up with, logged when found by name")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(server_option)
+        })
+        .create_option(|option| {
+            option
+                .name("set-channel")
+                .description("Post the playerlist in another channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option
+                        .name("channel")
+                        .description("Channel to post the playerlist in")
+                        .kind(CommandOptionType::Channel)
+                        .required(true)
+                })
+                .create_sub_option(server_option)
+        })
+        .create_option(|option| {
+            option
+                .name("set-game")
+                .description("Change the game of the followed server")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option
+                        .name("game")
+                        .description("Game the server is running")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+                    for game in Games::ALL {
+                        option.add_string_choice(game.to_string(), game.to_string());
+                    }
+                    option
+                })
+                .create_sub_option(server_option)
+        })
+        .create_option(|option| {
+            option
+                .name("reset-messages")
+                .description("Remove the playerlist messages and post new ones")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(server_option)
+        })
+}
+
+fn server_option(
+    option: &mut CreateApplicationCommandOption,
+) -> &mut CreateApplicationCommandOption {
+    option
+        .name("server")
+        .description("Server name from the config, not needed when following one server")
+        .kind(CommandOptionType::String)
+        .required(false)
+}
+
+fn option_str<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a str> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+}
+
+fn find_server<'a>(
+    servers: &'a mut [ServerConfig],
+    search: Option<&str>,
+) -> Result<&'a mut ServerConfig, String> {
+    let server_names: Vec<String> = servers
+        .iter()
+        .map(|server| server.server_name.clone())
+        .collect();
+    match search {
+        Some(search) => servers
+            .iter_mut()
+            .find(|server| server.server_name.eq_ignore_ascii_case(search))
+            .ok_or_else(|| {
+                format!(
+                    "No server named \"{}\", pick one of: {}",
+                    search,
+                    server_names.join(", ")
+                )
+            }),
+        None if servers.len() == 1 => Ok(&mut servers[0]),
+        None => Err(format!(
+            "Multiple servers are followed, pick one of: {}",
+            server_names.join(", ")
+        )),
+    }
+}
+
+/// let the update loop replace the messages, it's the only one editing them
+fn reset_messages(server: &mut ServerConfig) {
+    if server.pending_reset.is_none() {
+        server.pending_reset = Some(server.channel);
+    }
+}
+
+pub async fn run(command: &ApplicationCommandInteraction, config: &SharedConfig) -> String {
+    let permissions = command
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions);
+    if !matches!(permissions, Some(permissions) if permissions.manage_guild()) {
+        return "You need the Manage Server permission to change the playerlist".into();
+    }
+
+    let subcommand = match command.data.options.first() {
+        Some(subcommand) => subcommand,
+        None => return "No subcommand given".into(),
+    };
+    let options = &subcommand.options;
+
+    let mut cfg = config.write().await;
+    let server_names: Vec<String> = cfg
+        .servers
+        .iter()
+        .map(|server| server.server_name.clone())
+        .collect();
+    let server = match find_server(&mut cfg.servers, option_str(options, "server")) {
+        Ok(server) => server,
+        Err(e) => return e,
+    };
+
+    let mut renamed = None;
+    let response = match subcommand.name.as_str() {
+        "set-server" => {
+            let name = option_str(options, "name").unwrap_or_default();
+            if name.is_empty() {
+                return "No server name given".into();
+            }
+            // the update loop keeps track of the servers by name
+            if !server.server_name.eq_ignore_ascii_case(name)
+                && server_names
+                    .iter()
+                    .any(|server_name| server_name.eq_ignore_ascii_case(name))
+            {
+                return format!("{} is already followed", name);
+            }
+            let old_name = std::mem::replace(&mut server.server_name, name.into());
+            renamed = Some((old_name, server.server_name.clone()));
+            server.server_id = option_str(options, "server_id").map(|id| id.into());
+            format!("Now following {}", server.server_name)
+        }
+        "set-channel" => {
+            let channel = match option_str(options, "channel").and_then(|id| id.parse().ok()) {
+                Some(channel) => channel,
+                None => return "No channel given".into(),
+            };
+            reset_messages(server);
+            server.channel = channel;
+            format!(
+                "Posting the playerlist of {} in <#{}>",
+                server.server_name, channel
+            )
+        }
+        "set-game" => {
+            let game: Games = match option_str(options, "game").unwrap_or_default().parse() {
+                Ok(game) => game,
+                Err(e) => return e.to_string(),
+            };
+            server.game = game;
+            // the id belongs to the old game, look the server up by name again
+            server.server_id = None;
+            format!(
+                "{} is now followed as a {} server, looked up by name",
+                server.server_name, server.game
+            )
+        }
+        "reset-messages" => {
+            reset_messages(server);
+            format!("Posting new messages for {}", server.server_name)
+        }
+        _ => return "Unknown subcommand".into(),
+    };
+
+    // keep showing the same server in the bot's status
+    if let Some((old_name, new_name)) = renamed {
+        if cfg.presence_server.as_ref() == Some(&old_name) {
+            cfg.presence_server = Some(new_name);
+        }
+    }
+
+    if let Err(e) = confy::store_path(CONFIG_PATH, cfg.clone()) {
+        log::error!("Failed to save config: {:#?}", e);
+        return format!("{}, but saving config.txt failed", response);
+    }
+    format!("{}, changes are used on the next update", response)
+}
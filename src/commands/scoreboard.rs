@@ -0,0 +1,57 @@
+use serenity::{
+    builder::CreateApplicationCommand,
+    model::application::{
+        command::CommandOptionType, interaction::application_command::CommandDataOption,
+    },
+};
+
+use crate::{structs::snapshot::SnapshotCache, to_table};
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("scoreboard")
+        .description("Show the current playerlist of a followed server")
+        .create_option(|option| {
+            option
+                .name("server")
+                .description("(part of) the server name, not needed when following one server")
+                .kind(CommandOptionType::String)
+                .required(false)
+        })
+}
+
+pub async fn run(options: &[CommandDataOption], snapshots: &SnapshotCache) -> Vec<String> {
+    let search = options
+        .iter()
+        .find(|option| option.name == "server")
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .map(|server| server.to_lowercase());
+
+    let snapshots = snapshots.read().await;
+    let matches: Vec<_> = snapshots
+        .iter()
+        .filter(|(server_name, snapshot)| match &search {
+            Some(search) => {
+                server_name.to_lowercase().contains(search)
+                    || snapshot.server.name.to_lowercase().contains(search)
+            }
+            None => true,
+        })
+        .collect();
+
+    match matches.len() {
+        0 => vec!["No playerlist found for that server (yet)".into()],
+        1 => to_table::to_all_messages(matches[0].1, false).await,
+        _ => {
+            let mut server_names: Vec<&String> = matches
+                .iter()
+                .map(|(server_name, _)| *server_name)
+                .collect();
+            server_names.sort();
+            let mut lines = vec!["Multiple servers found, pick one of:".to_string()];
+            lines.extend(server_names.iter().map(|name| format!("- {}", name)));
+            vec![lines.join("\n")]
+        }
+    }
+}
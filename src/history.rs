@@ -0,0 +1,127 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use rusqlite::{params, Connection};
+
+use crate::structs::snapshot::Snapshot;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY,
+    server TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    map TEXT,
+    mode TEXT
+);
+CREATE INDEX IF NOT EXISTS snapshots_timestamp ON snapshots (timestamp);
+CREATE TABLE IF NOT EXISTS players (
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots (id),
+    team TEXT NOT NULL,
+    player_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    platoon TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    score INTEGER,
+    kills INTEGER,
+    deaths INTEGER,
+    ping INTEGER,
+    join_time INTEGER
+);
+CREATE INDEX IF NOT EXISTS players_snapshot ON players (snapshot_id);
+CREATE INDEX IF NOT EXISTS players_name ON players (name);
+";
+
+/// every snapshot of the update loop recorded in a sqlite database
+#[derive(Clone)]
+pub struct History {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl History {
+    pub fn open(path: &str) -> anyhow::Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("couldn't open history database \"{}\"", path))?;
+        connection
+            .execute_batch(SCHEMA)
+            .context("couldn't create the history tables")?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// store the map, mode and all players in the teams of a snapshot
+    pub async fn record(&self, server_name: &str, snapshot: &Snapshot) -> anyhow::Result<()> {
+        let connection = Arc::clone(&self.connection);
+        let server_name = server_name.to_owned();
+        let snapshot = snapshot.clone();
+        let timestamp = chrono::Utc::now().timestamp();
+
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection
+                .lock()
+                .map_err(|_| anyhow::anyhow!("history database lock is poisoned"))?;
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                "INSERT INTO snapshots (server, timestamp, map, mode) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    server_name,
+                    timestamp,
+                    snapshot.server.current_map,
+                    snapshot.server.mode
+                ],
+            )?;
+            let snapshot_id = transaction.last_insert_rowid();
+
+            {
+                let mut insert = transaction.prepare(
+                    "INSERT INTO players (snapshot_id, team, player_id, name, platoon, rank, score, kills, deaths, ping, join_time)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                )?;
+                for team in &snapshot.teams {
+                    for player in &team.players {
+                        insert.execute(params![
+                            snapshot_id,
+                            team.name,
+                            player.player_id,
+                            player.name,
+                            player.platoon,
+                            player.rank,
+                            player.score,
+                            player.kills,
+                            player.deaths,
+                            player.ping.map(|ping| ping as i64),
+                            player.join_time
+                        ])?;
+                    }
+                }
+            }
+            transaction.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// remove snapshots older than `days`, returns how many were removed
+    pub async fn prune(&self, days: u64) -> anyhow::Result<usize> {
+        let connection = Arc::clone(&self.connection);
+        let before = chrono::Utc::now().timestamp() - (days * 24 * 60 * 60) as i64;
+
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection
+                .lock()
+                .map_err(|_| anyhow::anyhow!("history database lock is poisoned"))?;
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                "DELETE FROM players WHERE snapshot_id IN (SELECT id FROM snapshots WHERE timestamp < ?1)",
+                params![before],
+            )?;
+            let removed = transaction.execute(
+                "DELETE FROM snapshots WHERE timestamp < ?1",
+                params![before],
+            )?;
+            transaction.commit()?;
+            Ok(removed)
+        })
+        .await?
+    }
+}
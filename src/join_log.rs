@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use crate::structs::snapshot::{Player, Snapshot};
+
+/// players of the last snapshot by player_id
+#[derive(Default)]
+pub struct JoinLogState {
+    players: Option<HashMap<String, Player>>,
+}
+
+/// "1h 12m" from a duration in seconds
+fn session_length(seconds: i64) -> String {
+    let minutes = seconds.max(0) / 60;
+    match minutes / 60 {
+        0 => format!("{}m", minutes),
+        hours => format!("{}h {}m", hours, minutes % 60),
+    }
+}
+
+/// join and leave lines since the last snapshot, nothing on the first one
+pub fn changes(snapshot: &Snapshot, state: &mut JoinLogState) -> Vec<String> {
+    let current: HashMap<String, Player> = snapshot
+        .teams
+        .iter()
+        .flat_map(|team| team.players.iter())
+        .map(|player| (player.player_id.clone(), player.clone()))
+        .collect();
+    let previous = match state.players.replace(current.clone()) {
+        Some(previous) => previous,
+        None => return vec![],
+    };
+
+    let mut lines = vec![];
+    for team in &snapshot.teams {
+        for player in &team.players {
+            if !previous.contains_key(&player.player_id) {
+                lines.push(format!("{} joined ({})", player.display_name(), team.name));
+            }
+        }
+    }
+
+    let mut left: Vec<&Player> = previous
+        .values()
+        .filter(|player| !current.contains_key(&player.player_id))
+        .collect();
+    left.sort_by_key(|player| player.name.to_lowercase());
+    let now = chrono::Utc::now().timestamp();
+    for player in left {
+        lines.push(match player.join_time {
+            Some(join_time) => format!(
+                "{} left after {}",
+                player.display_name(),
+                session_length(now - join_time / 1000000)
+            ),
+            None => format!("{} left", player.display_name()),
+        });
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::snapshot::Team;
+
+    fn snapshot(teams: Vec<(&str, Vec<Player>)>) -> Snapshot {
+        let mut snapshot = Snapshot::empty("Amiens", "Conquest");
+        snapshot.teams = teams
+            .into_iter()
+            .map(|(name, players)| Team {
+                name: name.into(),
+                image: None,
+                score: None,
+                players,
+            })
+            .collect();
+        snapshot
+    }
+
+    /// player that joined `seconds` ago
+    fn joined(name: &str, seconds: i64) -> Player {
+        let mut player = Player::named(name);
+        player.join_time = Some((chrono::Utc::now().timestamp() - seconds) * 1000000);
+        player
+    }
+
+    #[test]
+    fn first_snapshot_has_no_changes() {
+        let mut state = JoinLogState::default();
+        let first = snapshot(vec![("Team 1", vec![Player::named("a")])]);
+        assert!(changes(&first, &mut state).is_empty());
+        assert!(changes(&first, &mut state).is_empty());
+    }
+
+    #[test]
+    fn joins_show_the_team() {
+        let mut state = JoinLogState::default();
+        changes(
+            &snapshot(vec![("Team 1", vec![]), ("Team 2", vec![])]),
+            &mut state,
+        );
+
+        let mut platoon = Player::named("b");
+        platoon.platoon = "BoB".into();
+        let next = snapshot(vec![
+            ("Team 1", vec![Player::named("a")]),
+            ("Team 2", vec![platoon]),
+        ]);
+        assert_eq!(
+            changes(&next, &mut state),
+            vec!["a joined (Team 1)", "[BoB]b joined (Team 2)"]
+        );
+    }
+
+    #[test]
+    fn leaves_are_sorted_with_the_session_length() {
+        let mut state = JoinLogState::default();
+        changes(
+            &snapshot(vec![(
+                "Team 1",
+                vec![
+                    joined("zed", 72 * 60 + 30),
+                    joined("Bob", 5 * 60 + 30),
+                    Player::named("alice"),
+                    Player::named("stays"),
+                ],
+            )]),
+            &mut state,
+        );
+
+        let next = snapshot(vec![("Team 1", vec![Player::named("stays")])]);
+        assert_eq!(
+            changes(&next, &mut state),
+            vec!["alice left", "Bob left after 5m", "zed left after 1h 12m"]
+        );
+    }
+
+    #[test]
+    fn switching_teams_isnt_a_join() {
+        let mut state = JoinLogState::default();
+        changes(
+            &snapshot(vec![("Team 1", vec![Player::named("a")])]),
+            &mut state,
+        );
+        let next = snapshot(vec![
+            ("Team 1", vec![]),
+            ("Team 2", vec![Player::named("a")]),
+        ]);
+        assert!(changes(&next, &mut state).is_empty());
+    }
+
+    #[test]
+    fn formats_session_lengths() {
+        assert_eq!(session_length(59), "0m");
+        assert_eq!(session_length(12 * 60), "12m");
+        assert_eq!(session_length(60 * 60), "1h 0m");
+        assert_eq!(session_length(-5), "0m");
+    }
+}
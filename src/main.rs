@@ -1,26 +1,57 @@
 use serenity::{
     client::{Client, Context, EventHandler},
-    model::{gateway::Ready, prelude::ChannelId},
-    prelude::GatewayIntents,
+    model::{application::interaction::Interaction, gateway::Ready},
+    prelude::{GatewayIntents, RwLock},
 };
 use std::{
+    collections::HashMap,
     sync::{atomic, Arc},
-    time, vec,
+    time,
 };
+use structs::config::CONFIG_PATH;
 use warp::Filter;
+mod chat;
+mod commands;
+mod history;
+mod join_log;
+mod messages;
+mod round;
+mod sources;
+mod status;
 mod structs;
+mod to_embed;
 mod to_table;
 
-struct Handler;
+struct Handler {
+    config: structs::config::SharedConfig,
+    snapshots: structs::snapshot::SnapshotCache,
+    history: Option<history::History>,
+    /// `ready` fires again after every reconnect, the loop may only run once
+    started: atomic::AtomicBool,
+}
 
 #[serenity::async_trait]
 impl EventHandler for Handler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        if let Interaction::ApplicationCommand(command) = interaction {
+            commands::handle(&ctx, &command, &self.config, &self.snapshots).await;
+        }
+    }
+
     async fn ready(&self, ctx: Context, _: Ready) {
         let user = ctx.cache.current_user();
         log::info!("Logged in as {:#?}", user.name);
 
-        let mut cfg = get_config().await;
-        confy::store_path("config.txt", cfg.clone()).unwrap();
+        if let Err(e) = commands::register(&ctx).await {
+            log::error!("Failed to register slash commands: {:#?}", e);
+        }
+
+        if self.started.swap(true, atomic::Ordering::SeqCst) {
+            return;
+        }
+
+        let config = Arc::clone(&self.config);
+        confy::store_path(CONFIG_PATH, config.read().await.clone()).unwrap();
 
         let last_update = Arc::new(atomic::AtomicI64::new(0));
         let last_update_clone = Arc::clone(&last_update);
@@ -45,25 +76,112 @@ impl EventHandler for Handler {
             warp::serve(hello).run(([0, 0, 0, 0], 3030)).await;
         });
 
-        let client = reqwest::Client::new();
+        let client = reqwest::Client::builder()
+            .timeout(sources::retry::REQUEST_TIMEOUT)
+            .build()
+            .expect("Error creating http client");
+        let snapshots = Arc::clone(&self.snapshots);
+        let history = self.history.clone();
         // loop in seperate async
         tokio::spawn(async move {
+            let mut states: HashMap<String, ServerState> = HashMap::new();
+            let mut last_prune: Option<time::Instant> = None;
+            let mut presence: Option<String> = None;
             loop {
-                cfg = match gather_table(&cfg, &client).await {
-                    Ok((title, tables)) => {
-                        match send_info(&ctx, cfg.clone(), tables, title).await {
-                            Ok(cfg) => cfg,
-                            Err(e) => {
-                                log::error!("Couldn't send message: {:#?}", e);
-                                cfg
+                // picks up the changes made with the admin commands
+                let cfg = config.read().await.clone();
+
+                let mut server_states: Vec<ServerState> = cfg
+                    .servers
+                    .iter()
+                    .map(|server| states.remove(&server.server_name).unwrap_or_default())
+                    .collect();
+                let updates =
+                    cfg.servers
+                        .iter()
+                        .zip(server_states.iter_mut())
+                        .map(|(server, state)| {
+                            update_server(
+                                &ctx,
+                                &client,
+                                &snapshots,
+                                history.as_ref(),
+                                &cfg.api_url,
+                                server.clone(),
+                                state,
+                            )
+                        });
+                let results = futures::future::join_all(updates).await;
+
+                states = cfg
+                    .servers
+                    .iter()
+                    .map(|server| server.server_name.clone())
+                    .zip(server_states)
+                    .collect();
+                snapshots
+                    .write()
+                    .await
+                    .retain(|server_name, _| states.contains_key(server_name));
+
+                if let Some(template) = &cfg.presence {
+                    let snapshot = match cfg.presence_server() {
+                        Some(server_name) => snapshots.read().await.get(server_name).cloned(),
+                        None => None,
+                    };
+                    if let Some(snapshot) = snapshot {
+                        status::set_presence(&ctx, template, &snapshot, &mut presence).await;
+                    }
+                }
+
+                let mut current = config.write().await;
+                let mut changed = false;
+                for ((before, result), server) in cfg
+                    .servers
+                    .iter()
+                    .zip(results)
+                    .zip(current.servers.iter_mut())
+                {
+                    // the new message ids are always kept, they're in the channel of
+                    // this update. a reset a command asked for while updating has to
+                    // remove those messages, so it's done in that channel
+                    let reset_requested = server.pending_reset.is_some()
+                        && (server.pending_reset != before.pending_reset
+                            || server.channel != before.channel);
+                    let pending_reset = if reset_requested {
+                        Some(before.channel)
+                    } else {
+                        result.pending_reset
+                    };
+                    if server.messages != result.messages || server.pending_reset != pending_reset {
+                        server.messages = result.messages;
+                        server.pending_reset = pending_reset;
+                        changed = true;
+                    }
+                }
+                if changed {
+                    if let Err(e) = confy::store_path(CONFIG_PATH, current.clone()) {
+                        log::error!("Failed to save config: {:#?}", e);
+                    }
+                }
+                drop(current);
+
+                if let Some(history) = &history {
+                    let prune_due = match last_prune {
+                        Some(last_prune) => last_prune.elapsed() > PRUNE_INTERVAL,
+                        None => true,
+                    };
+                    if cfg.history_retention_days > 0 && prune_due {
+                        match history.prune(cfg.history_retention_days).await {
+                            Ok(removed) if removed > 0 => {
+                                log::info!("Removed {} old snapshots from the history", removed)
                             }
+                            Ok(_) => {}
+                            Err(e) => log::error!("Failed to prune the history: {:#?}", e),
                         }
+                        last_prune = Some(time::Instant::now());
                     }
-                    Err(e) => {
-                        log::error!("Couldn't get serverinfo: {:#?}", e);
-                        cfg
-                    }
-                };
+                }
 
                 // wait 30 seconds before redo
                 tokio::time::sleep(time::Duration::from_secs(30)).await;
@@ -72,186 +190,155 @@ impl EventHandler for Handler {
     }
 }
 
-async fn send_info(
-    ctx: &Context,
-    mut cfg: structs::config::SenderConfig,
-    tables: Vec<String>,
-    title: String,
-) -> anyhow::Result<structs::config::SenderConfig> {
-    if cfg.messages.len() >= 5 {
-        let mut index = 0;
-
-        match ChannelId(cfg.channel)
-            .edit_message(&ctx.http, cfg.messages[index], |m| {
-                m.content(format!("```\n{}\n```", title))
-            })
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => log::error!("Failed to send title message: {:#?}", e),
-        };
-
-        index += 1;
-
-        for content in tables.iter() {
-            let text: Vec<&str> = content.split('\n').collect();
-            let (first_message, second_message) = text.split_at(text.len() / 2);
-
-            match ChannelId(cfg.channel)
-                .edit_message(&ctx.http, cfg.messages[index], |m| {
-                    m.content(format!("```\n{}\n```", first_message.join("\n")))
-                })
-                .await
-            {
-                Ok(_) => {}
-                Err(e) => log::error!("Failed to send first team's message: {:#?}", e),
-            };
-
-            index += 1;
-
-            match ChannelId(cfg.channel)
-                .edit_message(&ctx.http, cfg.messages[index], |m| {
-                    m.content(format!("```\n{}\n```", second_message.join("\n")))
-                })
-                .await
-            {
-                Ok(_) => {}
-                Err(e) => log::error!("Failed to send second team's message: {:#?}", e),
-            };
-
-            index += 1;
-        }
-    } else {
-        log::info!("Message to edit not set, creating new...");
+/// how often snapshots past the retention are removed from the history
+const PRUNE_INTERVAL: time::Duration = time::Duration::from_secs(60 * 60);
 
-        cfg.messages = vec![];
+/// what the update loop remembers about a server between runs
+#[derive(Default)]
+struct ServerState {
+    logged_server_id: bool,
+    status_channel: status::RenameState,
+    sent: messages::SentMessages,
+    chat: chat::ChatState,
+    join_log: join_log::JoinLogState,
+    round: round::RoundState,
+}
 
-        match ChannelId(cfg.channel)
-            .send_message(&ctx.http, |m| m.content(format!("```\n{}\n```", title)))
-            .await
-        {
-            Ok(title_result) => {
-                cfg.messages.push(*title_result.id.as_u64());
+async fn update_server(
+    ctx: &Context,
+    client: &reqwest::Client,
+    snapshots: &structs::snapshot::SnapshotCache,
+    history: Option<&history::History>,
+    api_url: &str,
+    mut server: structs::config::ServerConfig,
+    state: &mut ServerState,
+) -> structs::config::ServerConfig {
+    if let Some(channel) = server.pending_reset.take() {
+        messages::remove_all(ctx, channel, &mut server.messages).await;
+        state.sent.clear();
+    }
+    match gather_table(api_url, &server, client, state).await {
+        Ok(snapshot) => {
+            // post the final scoreboard before the messages show the new round
+            if let Some(previous) = round::ended(&snapshot, &mut state.round) {
+                if let Some(round_summary_channel) = server.round_summary_channel {
+                    round::post_summary(ctx, round_summary_channel, &server, &previous).await;
+                }
             }
-            Err(e) => {
-                cfg.messages.clear();
-                anyhow::bail!("Failed to set message: {}", e);
+            let contents = messages::render(&server, &snapshot).await;
+            if let Err(e) = messages::send_info(ctx, &mut server, contents, &mut state.sent).await {
+                log::error!("Couldn't send message for {}: {:#?}", server.server_name, e);
             }
-        };
-
-        for content in tables.iter() {
-            let text: Vec<&str> = content.split('\n').collect();
-            let (first_message, second_message) = text.split_at(text.len() / 2);
-
-            match ChannelId(cfg.channel)
-                .send_message(&ctx.http, |m| {
-                    m.content(format!("```\n{}\n```", first_message.join("\n")))
-                })
-                .await
-            {
-                Ok(first_result) => {
-                    cfg.messages.push(*first_result.id.as_u64());
-                }
-                Err(e) => {
-                    cfg.messages.clear();
-                    anyhow::bail!("Failed to set message: {}", e);
+            let new_chat = chat::new_messages(&snapshot, &mut state.chat);
+            if let Some(chat_channel) = server.chat_channel {
+                chat::relay(ctx, chat_channel, &snapshot, &new_chat).await;
+            }
+            chat::alert(ctx, &server, &snapshot, &new_chat, &mut state.chat).await;
+            let joins = join_log::changes(&snapshot, &mut state.join_log);
+            if let Some(join_log_channel) = server.join_log_channel {
+                messages::post_batched(ctx, join_log_channel, &joins).await;
+            }
+            if let Some(status_channel) = &server.status_channel {
+                status::rename_channel(ctx, status_channel, &snapshot, &mut state.status_channel)
+                    .await;
+            }
+            if let Some(history) = history {
+                if let Err(e) = history.record(&server.server_name, &snapshot).await {
+                    log::error!(
+                        "Failed to record history for {}: {:#?}",
+                        server.server_name,
+                        e
+                    );
                 }
-            };
-
-            match ChannelId(cfg.channel)
-                .send_message(&ctx.http, |m| {
-                    m.content(format!("```\n{}\n```", second_message.join("\n")))
-                })
+            }
+            snapshots
+                .write()
                 .await
-            {
-                Ok(second_result) => {
-                    cfg.messages.push(*second_result.id.as_u64());
-                }
-                Err(e) => {
-                    cfg.messages.clear();
-                    anyhow::bail!("Failed to set message: {}", e);
-                }
-            };
+                .insert(server.server_name.clone(), snapshot);
+        }
+        Err(e) => {
+            log::error!(
+                "Couldn't get serverinfo for {}: {:#?}",
+                server.server_name,
+                e
+            );
         }
-        confy::store_path("config.txt", cfg.clone()).unwrap();
     }
-    Ok(cfg)
+    server
 }
 
 async fn gather_table(
-    cfg: &structs::config::SenderConfig,
+    api_url: &str,
+    cfg: &structs::config::ServerConfig,
     client: &reqwest::Client,
-) -> anyhow::Result<(String, Vec<String>)> {
-    Ok(match cfg.game {
-        structs::config::Games::Bf1 => {
-            let result = match to_table::player_list::request_player_list(&cfg.server_name, client)
-                .await
-            {
-                Ok(result) => result,
-                // retry
-                Err(_) => {
-                    match to_table::player_list::request_player_list(&cfg.server_name, client).await
-                    {
-                        Ok(result) => result,
-                        Err(e) => anyhow::bail!("Couldn't get bf1 playerlist {:#?}", e),
-                    }
-                }
-            };
-
-            let (title, tables) =
-                match to_table::seeder_player_list::request_player_list(&cfg.server_name, client)
-                    .await
-                {
-                    Ok(seeder_result) => {
-                        to_table::seeder_player_list::to_tables(&seeder_result, &result.clone())
-                            .await
-                    }
-                    Err(_) => to_table::player_list::to_tables(&result).await,
-                };
+    state: &mut ServerState,
+) -> anyhow::Result<structs::snapshot::Snapshot> {
+    let snapshot = sources::for_game(&cfg.game)
+        .fetch(api_url, cfg, client)
+        .await?;
 
-            (title, tables)
+    if cfg.server_id.is_none() && !state.logged_server_id {
+        if let Some(server_id) = &snapshot.server.server_id {
+            log::info!(
+                "Found {} with server_id \"{}\", add it to config.txt to stop searching by name",
+                cfg.server_name,
+                server_id
+            );
+            state.logged_server_id = true;
         }
-        structs::config::Games::Bf4 => {
-            let result = match to_table::bf4_player_list::request_player_list(
-                &cfg.server_name,
-                client,
-            )
-            .await
-            {
-                Ok(result) => result,
-                Err(e) => anyhow::bail!("Couldn't get bf4 playerlist {:#?}", e),
-            };
+    }
 
-            to_table::bf4_player_list::to_tables(&result).await
-        }
-    })
+    Ok(snapshot)
 }
 
-async fn get_config() -> structs::config::SenderConfig {
-    let cfg: structs::config::SenderConfig = match confy::load_path("config.txt") {
+async fn get_config() -> anyhow::Result<structs::config::SenderConfig> {
+    let mut cfg: structs::config::SenderConfig = match confy::load_path(CONFIG_PATH) {
         Ok(config) => config,
-        Err(e) => {
-            log::error!("error in config.txt: {}", e);
-            log::warn!("changing back to default..");
-            structs::config::SenderConfig {
-                server_name: "".into(),
-                token: "".into(),
-                channel: 0,
-                messages: vec![],
-                game: structs::config::Games::from("bf1"),
-            }
-        }
+        Err(e) => return Err(anyhow::Error::new(e).context("error in config.txt")),
     };
-    if cfg.server_name.is_empty() {
-        log::error!("servername isn't set!");
+    if cfg.migrate() {
+        log::info!("Moved the server in config.txt to [[servers]], it's saved in the new layout");
     }
     if cfg.token.is_empty() {
         log::error!("token isn't set!");
     }
-    if cfg.token.is_empty() {
-        log::error!("channel isn't set!");
+    if let Err(e) = reqwest::Url::parse(&cfg.api_url) {
+        anyhow::bail!("api_url \"{}\" isn't a valid url: {}", cfg.api_url, e);
+    }
+    if cfg.servers.is_empty() {
+        log::error!("no servers are set!");
+    }
+    if let Some(presence_server) = &cfg.presence_server {
+        if !cfg
+            .servers
+            .iter()
+            .any(|server| &server.server_name == presence_server)
+        {
+            anyhow::bail!(
+                "presence_server \"{}\" isn't one of the servers",
+                presence_server
+            );
+        }
+    }
+    for (index, server) in cfg.servers.iter().enumerate() {
+        // the update loop keeps track of the servers by name
+        if cfg.servers[..index]
+            .iter()
+            .any(|other| other.server_name.eq_ignore_ascii_case(&server.server_name))
+        {
+            anyhow::bail!("{} is in servers more than once", server.server_name);
+        }
+        if server.server_name.is_empty() {
+            log::error!("servername isn't set!");
+        }
+        if server.channel == 0 {
+            log::error!("channel isn't set for {}!", server.server_name);
+        }
+        if let Some(alerts) = &server.chat_alerts {
+            chat::validate_rules(&alerts.rules)?;
+        }
     }
-    cfg
+    Ok(cfg)
 }
 
 #[tokio::main]
@@ -260,12 +347,22 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or_else(|e| panic!("Logger initialization failed with {}", e))
         .start()?;
 
-    let cfg = get_config().await;
+    // refuse to start with a config that can't be used
+    let cfg = get_config().await?;
+    let history = match &cfg.history_database {
+        Some(path) => Some(history::History::open(path)?),
+        None => None,
+    };
 
     // Login with a bot token from the environment
     let intents = GatewayIntents::non_privileged();
-    let mut client = Client::builder(cfg.token, intents)
-        .event_handler(Handler)
+    let mut client = Client::builder(&cfg.token, intents)
+        .event_handler(Handler {
+            config: Arc::new(RwLock::new(cfg)),
+            snapshots: Default::default(),
+            history,
+            started: atomic::AtomicBool::new(false),
+        })
         .await
         .expect("Error creating client");
 
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use serenity::{builder::CreateEmbed, client::Context, model::prelude::ChannelId};
+
+use crate::{structs, to_embed, to_table};
+
+#[derive(Clone)]
+pub enum MessageContent {
+    Text(String),
+    Embed(CreateEmbed),
+}
+
+impl PartialEq for MessageContent {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MessageContent::Text(text), MessageContent::Text(other)) => text == other,
+            (MessageContent::Embed(embed), MessageContent::Embed(other)) => embed.0 == other.0,
+            _ => false,
+        }
+    }
+}
+
+/// discord's limit on the length of a message
+pub const MESSAGE_LIMIT: usize = 2000;
+
+/// every message is edited at least this often, even without changes, as an
+/// edit is the only way a removed message is noticed
+const FORCED_EDIT_INTERVAL: usize = 10;
+
+/// what was last sent, to skip edits that don't change anything
+#[derive(Default)]
+pub struct SentMessages {
+    contents: HashMap<u64, MessageContent>,
+    /// updates since every message was edited
+    updates: usize,
+}
+
+impl SentMessages {
+    pub fn clear(&mut self) {
+        self.contents.clear();
+    }
+}
+
+pub async fn render(
+    server: &structs::config::ServerConfig,
+    snapshot: &structs::snapshot::Snapshot,
+) -> Vec<MessageContent> {
+    match server.output {
+        structs::config::Output::Text => to_table::to_all_messages(snapshot, server.details)
+            .await
+            .into_iter()
+            .map(MessageContent::Text)
+            .collect(),
+        structs::config::Output::Embed => to_embed::to_embeds(snapshot, server.details)
+            .into_iter()
+            .map(MessageContent::Embed)
+            .collect(),
+    }
+}
+
+/// keep the tracked messages of a server in sync with the rendered contents,
+/// messages that are the same as in `sent` are only edited every
+/// `FORCED_EDIT_INTERVAL` updates
+pub async fn send_info(
+    ctx: &Context,
+    cfg: &mut structs::config::ServerConfig,
+    contents: Vec<MessageContent>,
+    sent: &mut SentMessages,
+) -> anyhow::Result<()> {
+    sent.updates += 1;
+    if sent.updates >= FORCED_EDIT_INTERVAL {
+        sent.updates = 0;
+        sent.clear();
+    }
+
+    let channel = ChannelId(cfg.channel);
+    let mut recreate = false;
+    for (message_id, content) in cfg.messages.iter().zip(contents.iter()) {
+        if sent.contents.get(message_id) == Some(content) {
+            continue;
+        }
+        match channel
+            .edit_message(&ctx.http, *message_id, |m| match content {
+                MessageContent::Text(text) => m.content(text).set_embeds(vec![]),
+                MessageContent::Embed(embed) => m.content("").set_embed(embed.clone()),
+            })
+            .await
+        {
+            Ok(_) => {
+                sent.contents.insert(*message_id, content.clone());
+            }
+            Err(e) if is_unknown_message(&e) => {
+                log::warn!(
+                    "Message {} was removed, recreating the messages for {}...",
+                    message_id,
+                    cfg.server_name
+                );
+                recreate = true;
+                break;
+            }
+            Err(e) => log::error!("Failed to edit message {}: {:#?}", message_id, e),
+        }
+    }
+
+    // resend all of them to keep the messages in order
+    if recreate {
+        remove_all(ctx, cfg.channel, &mut cfg.messages).await;
+    }
+
+    // the layout grew, add messages below the current ones
+    if cfg.messages.is_empty() {
+        log::info!("Message to edit not set, creating new...");
+    }
+    for content in contents.iter().skip(cfg.messages.len()) {
+        match channel
+            .send_message(&ctx.http, |m| match content {
+                MessageContent::Text(text) => m.content(text),
+                MessageContent::Embed(embed) => m.set_embed(embed.clone()),
+            })
+            .await
+        {
+            Ok(message) => {
+                cfg.messages.push(*message.id.as_u64());
+                sent.contents.insert(*message.id.as_u64(), content.clone());
+            }
+            Err(e) => anyhow::bail!("Failed to set message: {}", e),
+        }
+    }
+
+    // the layout shrunk, remove the messages that aren't used anymore
+    while cfg.messages.len() > contents.len() {
+        if let Some(message_id) = cfg.messages.pop() {
+            if let Err(e) = channel.delete_message(&ctx.http, message_id).await {
+                log::error!("Failed to remove unused message: {:#?}", e);
+            }
+        }
+    }
+    sent.contents
+        .retain(|message_id, _| cfg.messages.contains(message_id));
+    Ok(())
+}
+
+/// post lines together in as few messages as possible, cutting off lines
+/// that don't fit in a message by themselves
+pub async fn post_batched(ctx: &Context, channel: u64, lines: &[String]) {
+    let mut batches: Vec<String> = vec![];
+    for line in lines {
+        match batches.last_mut() {
+            Some(batch) if batch.chars().count() + line.chars().count() < MESSAGE_LIMIT => {
+                batch.push('\n');
+                batch.push_str(line);
+            }
+            _ => batches.push(line.chars().take(MESSAGE_LIMIT).collect()),
+        }
+    }
+
+    for batch in batches {
+        if let Err(e) = ChannelId(channel)
+            .send_message(&ctx.http, |m| {
+                m.content(batch)
+                    .allowed_mentions(|mentions| mentions.empty_parse())
+            })
+            .await
+        {
+            log::error!("Failed to post in channel {}: {:#?}", channel, e);
+        }
+    }
+}
+
+/// remove the messages of a server, used to start over with new ones
+pub async fn remove_all(ctx: &Context, channel: u64, messages: &mut Vec<u64>) {
+    for message_id in messages.drain(..) {
+        if let Err(e) = ChannelId(channel)
+            .delete_message(&ctx.http, message_id)
+            .await
+        {
+            if !is_unknown_message(&e) {
+                log::error!("Failed to remove message {}: {:#?}", message_id, e);
+            }
+        }
+    }
+}
+
+/// discord's error code for a message that doesn't exist (anymore)
+const UNKNOWN_MESSAGE: isize = 10008;
+
+fn is_unknown_message(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(http_error) => matches!(
+            http_error.as_ref(),
+            serenity::http::HttpError::UnsuccessfulRequest(response)
+                if response.error.code == UNKNOWN_MESSAGE
+        ),
+        _ => false,
+    }
+}
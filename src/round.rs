@@ -0,0 +1,234 @@
+use serenity::{client::Context, model::prelude::ChannelId};
+
+use crate::{
+    messages::MessageContent,
+    structs::{
+        config::{Output, ServerConfig},
+        snapshot::{Player, Snapshot, Team},
+    },
+    to_embed, to_table,
+};
+
+/// players listed in every top list of the summary
+pub const TOP_PLAYERS: usize = 5;
+
+/// last snapshot of the running round
+#[derive(Default)]
+pub struct RoundState {
+    previous: Option<Snapshot>,
+}
+
+/// only counts as a change if both values are known
+fn changed(before: &Option<String>, after: &Option<String>) -> bool {
+    matches!((before, after), (Some(before), Some(after)) if before != after)
+}
+
+/// team scores up to this count as the start of a new round
+pub const NEW_ROUND_SCORE: i64 = 10;
+
+/// only counts as a reset if every known team score went down, or they went down
+/// to (near) zero, a single team losing a few points doesn't end the round
+fn score_reset(before: &[Team], after: &[Team]) -> bool {
+    let scores: Vec<(i64, i64)> = before
+        .iter()
+        .zip(after.iter())
+        .filter_map(|(before, after)| Some((before.score?, after.score?)))
+        .collect();
+    let any_dropped = scores.iter().any(|(before, after)| after < before);
+    let all_dropped = scores.iter().all(|(before, after)| after < before);
+    let near_zero = scores.iter().all(|(_, after)| *after <= NEW_ROUND_SCORE);
+
+    any_dropped && (all_dropped || near_zero)
+}
+
+/// the final snapshot of the last round if `snapshot` is from a new one,
+/// a round ends when the map or mode changes or the team scores start over
+pub fn ended(snapshot: &Snapshot, state: &mut RoundState) -> Option<Snapshot> {
+    let previous = state.previous.replace(snapshot.clone())?;
+
+    let map_changed = changed(&previous.server.current_map, &snapshot.server.current_map)
+        || changed(&previous.server.mode, &snapshot.server.mode);
+    let score_reset = score_reset(&previous.teams, &snapshot.teams);
+    let had_players = previous.teams.iter().any(|team| !team.players.is_empty());
+
+    if (map_changed || score_reset) && had_players {
+        Some(previous)
+    } else {
+        None
+    }
+}
+
+fn players(snapshot: &Snapshot) -> impl Iterator<Item = (&Player, &Team)> {
+    snapshot
+        .teams
+        .iter()
+        .flat_map(|team| team.players.iter().map(move |player| (player, team)))
+}
+
+/// players with the highest score, empty if the game doesn't have scores
+pub fn top_score(snapshot: &Snapshot) -> Vec<(&Player, &Team)> {
+    let mut players: Vec<(&Player, &Team)> = players(snapshot)
+        .filter(|(player, _)| player.score.is_some())
+        .collect();
+    players.sort_by_key(|(player, _)| std::cmp::Reverse(player.score));
+    players.truncate(TOP_PLAYERS);
+    players
+}
+
+/// kills per death, players without deaths count as one death
+pub fn kd(player: &Player) -> Option<f64> {
+    match (player.kills, player.deaths) {
+        (Some(kills), Some(deaths)) => Some(kills as f64 / deaths.max(1) as f64),
+        _ => None,
+    }
+}
+
+/// players with the highest K/D and at least one kill, empty if the game doesn't have kills
+pub fn top_kd(snapshot: &Snapshot) -> Vec<(&Player, &Team)> {
+    let mut players: Vec<(&Player, &Team)> = players(snapshot)
+        .filter(|(player, _)| matches!(player.kills, Some(kills) if kills > 0))
+        .filter(|(player, _)| kd(player).is_some())
+        .collect();
+    players.sort_by(|(a, _), (b, _)| {
+        kd(b)
+            .partial_cmp(&kd(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    players.truncate(TOP_PLAYERS);
+    players
+}
+
+/// post the final scoreboard of a round as a new message that isn't edited afterwards
+pub async fn post_summary(ctx: &Context, channel: u64, server: &ServerConfig, snapshot: &Snapshot) {
+    let content = match server.output {
+        Output::Text => MessageContent::Text(to_table::round_summary(snapshot)),
+        Output::Embed => MessageContent::Embed(to_embed::round_summary(snapshot)),
+    };
+
+    if let Err(e) = ChannelId(channel)
+        .send_message(&ctx.http, |m| {
+            match content {
+                MessageContent::Text(text) => m.content(text),
+                MessageContent::Embed(embed) => m.set_embed(embed),
+            };
+            m.allowed_mentions(|mentions| mentions.empty_parse())
+        })
+        .await
+    {
+        log::error!(
+            "Failed to post the round summary of {}: {:#?}",
+            server.server_name,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(map: &str, mode: &str, scores: [Option<i64>; 2]) -> Snapshot {
+        let mut snapshot = Snapshot::empty(map, mode);
+        snapshot.teams = scores
+            .iter()
+            .enumerate()
+            .map(|(index, score)| Team {
+                name: format!("Team {}", index + 1),
+                image: None,
+                score: *score,
+                players: vec![Player::named(&format!("player{}", index))],
+            })
+            .collect();
+        snapshot
+    }
+
+    #[test]
+    fn first_snapshot_starts_the_round() {
+        let mut state = RoundState::default();
+        assert!(ended(&snapshot("Amiens", "Conquest", [None, None]), &mut state).is_none());
+    }
+
+    #[test]
+    fn rising_scores_continue_the_round() {
+        let mut state = RoundState::default();
+        ended(
+            &snapshot("Amiens", "Conquest", [Some(10), Some(20)]),
+            &mut state,
+        );
+        let next = snapshot("Amiens", "Conquest", [Some(15), Some(20)]);
+        assert!(ended(&next, &mut state).is_none());
+    }
+
+    #[test]
+    fn map_change_ends_the_round() {
+        let mut state = RoundState::default();
+        ended(&snapshot("Amiens", "Conquest", [None, None]), &mut state);
+        let last = ended(&snapshot("Suez", "Conquest", [None, None]), &mut state).unwrap();
+        assert_eq!(last.server.current_map.as_deref(), Some("Amiens"));
+        // the new round doesn't end again on the next update
+        assert!(ended(&snapshot("Suez", "Conquest", [None, None]), &mut state).is_none());
+    }
+
+    #[test]
+    fn mode_change_ends_the_round() {
+        let mut state = RoundState::default();
+        ended(&snapshot("Amiens", "Conquest", [None, None]), &mut state);
+        assert!(ended(&snapshot("Amiens", "Rush", [None, None]), &mut state).is_some());
+    }
+
+    #[test]
+    fn score_reset_ends_the_round() {
+        let mut state = RoundState::default();
+        ended(
+            &snapshot("Amiens", "Conquest", [Some(980), Some(1000)]),
+            &mut state,
+        );
+        let last = ended(
+            &snapshot("Amiens", "Conquest", [Some(0), Some(0)]),
+            &mut state,
+        )
+        .unwrap();
+        assert_eq!(last.teams[1].score, Some(1000));
+    }
+
+    #[test]
+    fn one_team_losing_score_continues_the_round() {
+        let mut state = RoundState::default();
+        ended(
+            &snapshot("Amiens", "Conquest", [Some(980), Some(1000)]),
+            &mut state,
+        );
+        let next = snapshot("Amiens", "Conquest", [Some(970), Some(1000)]);
+        assert!(ended(&next, &mut state).is_none());
+    }
+
+    #[test]
+    fn scores_near_zero_end_the_round() {
+        let mut state = RoundState::default();
+        ended(
+            &snapshot("Amiens", "Conquest", [Some(300), Some(2)]),
+            &mut state,
+        );
+        let next = snapshot("Amiens", "Conquest", [Some(4), Some(2)]);
+        assert!(ended(&next, &mut state).is_some());
+    }
+
+    #[test]
+    fn unknown_values_dont_end_the_round() {
+        let mut state = RoundState::default();
+        ended(
+            &snapshot("Amiens", "Conquest", [Some(50), None]),
+            &mut state,
+        );
+        let mut next = snapshot("Amiens", "Conquest", [None, None]);
+        next.server.current_map = None;
+        assert!(ended(&next, &mut state).is_none());
+    }
+
+    #[test]
+    fn empty_rounds_have_no_summary() {
+        let mut state = RoundState::default();
+        ended(&Snapshot::empty("Amiens", "Conquest"), &mut state);
+        assert!(ended(&Snapshot::empty("Suez", "Conquest"), &mut state).is_none());
+    }
+}
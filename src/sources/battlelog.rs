@@ -0,0 +1,205 @@
+use anyhow::Result;
+
+use crate::structs::{
+    self,
+    snapshot::{Player, Server, Snapshot, Team},
+};
+
+/// Bf3, Bf4 and Bfh, which share the same detailed server response
+pub struct BattlelogSource {
+    pub game: &'static str,
+}
+
+#[serenity::async_trait]
+impl super::GameSource for BattlelogSource {
+    async fn fetch(
+        &self,
+        api_url: &str,
+        server: &structs::config::ServerConfig,
+        client: &reqwest::Client,
+    ) -> Result<Snapshot> {
+        let result = match request_player_list(api_url, self.game, server, client).await {
+            Ok(result) => result,
+            Err(e) => anyhow::bail!("Couldn't get {} playerlist {:#?}", self.game, e),
+        };
+
+        Ok(to_snapshot(result))
+    }
+}
+
+pub async fn request_player_list(
+    api_url: &str,
+    game: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::bf4_player_list::DetailedServerInfo> {
+    let mut url = super::endpoint(api_url, &format!("{}/detailedserver/", game))?;
+    super::server_query(&mut url, server);
+
+    super::retry::DEFAULT_POLICY
+        .get_json::<structs::bf4_player_list::DetailedServerInfo>(client, url)
+        .await
+}
+
+fn to_snapshot(result: structs::bf4_player_list::DetailedServerInfo) -> Snapshot {
+    let teams = match (result.teams, result.players) {
+        (Some(teams), _) => teams
+            .into_iter()
+            .map(|team| Team {
+                name: team.teamid,
+                image: None,
+                score: None,
+                players: team.players.into_iter().map(to_player).collect(),
+            })
+            .collect(),
+        // empty or unranked servers only return a flat list of players
+        (None, Some(players)) if !players.is_empty() => vec![Team {
+            name: "Players".into(),
+            image: None,
+            score: None,
+            players: players.into_iter().map(to_player).collect(),
+        }],
+        (None, _) => vec![],
+    };
+
+    Snapshot {
+        server: Server {
+            name: result.prefix,
+            server_id: result.game_id,
+            current_map: Some(result.current_map),
+            mode: Some(result.mode),
+            favorites: Some(result.favorites),
+            max_players: result.max_player_amount,
+            in_queue: result.in_queue,
+            loading: None,
+        },
+        details: None,
+        teams,
+        queue: vec![],
+        loading: vec![],
+        chat: vec![],
+        updates: vec![],
+    }
+}
+
+/// squads are named like ingame, 0 is no squad
+const SQUADS: [&str; 32] = [
+    "Alpha",
+    "Bravo",
+    "Charlie",
+    "Delta",
+    "Echo",
+    "Foxtrot",
+    "Golf",
+    "Hotel",
+    "India",
+    "Juliet",
+    "Kilo",
+    "Lima",
+    "Mike",
+    "November",
+    "Oscar",
+    "Papa",
+    "Quebec",
+    "Romeo",
+    "Sierra",
+    "Tango",
+    "Uniform",
+    "Victor",
+    "Whiskey",
+    "X-ray",
+    "Yankee",
+    "Zulu",
+    "Haggard",
+    "Sweetwater",
+    "Preston",
+    "Redford",
+    "Faith",
+    "Celeste",
+];
+
+fn squad_name(squad: i64) -> Option<String> {
+    match usize::try_from(squad) {
+        Ok(0) | Err(_) => None,
+        Ok(squad) => Some(
+            SQUADS
+                .get(squad - 1)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| squad.to_string()),
+        ),
+    }
+}
+
+fn to_player(player: structs::bf4_player_list::ScoreServerPlayer) -> Player {
+    Player {
+        player_id: player.player_id,
+        name: player.name,
+        platoon: player.tag,
+        rank: player.rank,
+        squad: squad_name(player.squad),
+        // battlelog only has the role id, not the kit the player uses
+        class: Some(player.role.to_string()),
+        score: Some(player.score),
+        kills: Some(player.kills),
+        deaths: Some(player.deaths),
+        ping: None,
+        join_time: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_info(extra: &str) -> structs::bf4_player_list::DetailedServerInfo {
+        serde_json::from_str(&format!(
+            r#"{{"prefix": "Test server", "currentMap": "Zavod 311", "mode": "Conquest", "favorites": "12"{}}}"#,
+            extra
+        ))
+        .unwrap()
+    }
+
+    const PLAYER: &str = r#"{"kills": 3, "deaths": 1, "name": "player", "player_id": "1", "rank": 140, "role": 1, "score": 250, "squad": 2, "tag": "BoB"}"#;
+
+    #[test]
+    fn falls_back_to_the_flat_player_list() {
+        let snapshot = to_snapshot(server_info(&format!(r#", "players": [{}]"#, PLAYER)));
+        assert_eq!(snapshot.teams.len(), 1);
+        assert_eq!(snapshot.teams[0].name, "Players");
+        assert_eq!(snapshot.teams[0].players[0].name, "player");
+    }
+
+    #[test]
+    fn keeps_squad_and_role() {
+        let snapshot = to_snapshot(server_info(&format!(r#", "players": [{}]"#, PLAYER)));
+        let player = &snapshot.teams[0].players[0];
+        assert_eq!(player.squad.as_deref(), Some("Bravo"));
+        assert_eq!(player.class.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn names_squads_like_ingame() {
+        assert_eq!(squad_name(0), None);
+        assert_eq!(squad_name(1).as_deref(), Some("Alpha"));
+        assert_eq!(squad_name(32).as_deref(), Some("Celeste"));
+        assert_eq!(squad_name(33).as_deref(), Some("33"));
+    }
+
+    #[test]
+    fn server_without_players_has_no_teams() {
+        assert!(to_snapshot(server_info("")).teams.is_empty());
+        assert!(to_snapshot(server_info(r#", "players": []"#))
+            .teams
+            .is_empty());
+    }
+
+    #[test]
+    fn uses_the_teams_if_there_are_any() {
+        let snapshot = to_snapshot(server_info(&format!(
+            r#", "teams": [{{"teamid": "US", "players": [{}]}}], "players": [{}]"#,
+            PLAYER, PLAYER
+        )));
+        assert_eq!(snapshot.teams.len(), 1);
+        assert_eq!(snapshot.teams[0].name, "US");
+    }
+}
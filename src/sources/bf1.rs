@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::structs::{
+    self,
+    snapshot::{ChatMessage, Player, Server, ServerDetails, Snapshot, SourceUpdate, Team},
+};
+
+pub struct Bf1Source;
+
+#[serenity::async_trait]
+impl super::GameSource for Bf1Source {
+    async fn fetch(
+        &self,
+        api_url: &str,
+        server: &structs::config::ServerConfig,
+        client: &reqwest::Client,
+    ) -> Result<Snapshot> {
+        let result = match request_player_list(api_url, server, client).await {
+            Ok(result) => result,
+            Err(e) => anyhow::bail!("Couldn't get bf1 playerlist {:#?}", e),
+        };
+
+        let seeder_result = request_seeder_player_list(api_url, server, client)
+            .await
+            .ok();
+        // only used for the max players and favorites
+        let detailed_result = request_detailed_server(api_url, server, client).await.ok();
+
+        Ok(to_snapshot(result, seeder_result, detailed_result))
+    }
+}
+
+pub async fn request_player_list(
+    api_url: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::player_list::PlayerList> {
+    let mut url = super::endpoint(api_url, "bf1/players/")?;
+    super::server_query(&mut url, server);
+
+    super::retry::DEFAULT_POLICY
+        .get_json::<structs::player_list::PlayerList>(client, url)
+        .await
+}
+
+pub async fn request_detailed_server(
+    api_url: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::player_list::DetailedServerInfo> {
+    let mut url = super::endpoint(api_url, "bf1/detailedserver/")?;
+    super::server_query(&mut url, server);
+
+    super::retry::DEFAULT_POLICY
+        .get_json::<structs::player_list::DetailedServerInfo>(client, url)
+        .await
+}
+
+pub async fn request_seeder_player_list(
+    api_url: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::seeder_player_list::SeederPlayerList> {
+    let mut url = super::endpoint(api_url, "bf1/seederplayers/")?;
+    super::server_query(&mut url, server);
+    let result = super::retry::DEFAULT_POLICY
+        .get_json::<structs::seeder_player_list::SeederPlayerList>(client, url)
+        .await?;
+
+    let timestamp = chrono::DateTime::<Utc>::from_utc(
+        chrono::NaiveDateTime::from_timestamp_opt(result.update_timestamp, 0).unwrap_or_default(),
+        Utc,
+    );
+    let current = chrono::Utc::now();
+
+    // use only main playerlist if data is to old
+    if (current - timestamp).num_seconds() > 30 {
+        anyhow::bail!("Playerlist to old to be usable")
+    }
+    Ok(result)
+}
+
+fn to_snapshot(
+    result: structs::player_list::PlayerList,
+    seeder_result: Option<structs::seeder_player_list::SeederPlayerList>,
+    detailed_result: Option<structs::player_list::DetailedServerInfo>,
+) -> Snapshot {
+    let updates = vec![
+        SourceUpdate {
+            label: "Gametools update".into(),
+            timestamp: Some(result.update_timestamp),
+        },
+        SourceUpdate {
+            label: "Gather update".into(),
+            timestamp: seeder_result.as_ref().map(|seeder| seeder.update_timestamp),
+        },
+    ];
+
+    let (detailed_id, favorites, max_players) = match detailed_result {
+        Some(detailed) => (
+            detailed.game_id,
+            detailed.favorites,
+            detailed.max_player_amount,
+        ),
+        None => (None, None, None),
+    };
+    let server_id = seeder_result
+        .as_ref()
+        .map(|seeder| seeder.game_id.to_string())
+        .or(detailed_id);
+    let chat = match &seeder_result {
+        Some(seeder_result) => seeder_result
+            .ingame_chat
+            .iter()
+            .map(|message| ChatMessage {
+                timestamp: message.timestamp.clone(),
+                sender: message.sender.clone(),
+                content: message.content.clone(),
+            })
+            .collect(),
+        None => vec![],
+    };
+    let teams = match seeder_result {
+        Some(seeder_result) => seeder_teams(seeder_result, &result),
+        None => result.teams.iter().map(player_list_team).collect(),
+    };
+
+    Snapshot {
+        server: Server {
+            name: result.serverinfo.name,
+            server_id,
+            current_map: Some(result.serverinfo.level),
+            mode: Some(result.serverinfo.mode),
+            favorites,
+            max_players,
+            in_queue: Some(result.que.len() as u64),
+            loading: Some(result.loading.len() as u64),
+        },
+        details: Some(ServerDetails {
+            description: result.serverinfo.description,
+            region: result.serverinfo.region,
+            country: result.serverinfo.country,
+            owner: result.serverinfo.owner,
+            maps: result.serverinfo.maps.unwrap_or_default(),
+            settings: result.serverinfo.settings.unwrap_or_default(),
+        }),
+        teams,
+        queue: result.que.into_iter().map(to_player).collect(),
+        loading: result.loading.into_iter().map(to_player).collect(),
+        chat,
+        updates,
+    }
+}
+
+fn player_list_team(team: &structs::player_list::GameTeam) -> Team {
+    let mut sorted_players = team.players.clone();
+    sorted_players.sort_by_key(|item| item.slot);
+
+    Team {
+        name: team.name.clone(),
+        image: Some(team.image.clone()),
+        score: None,
+        players: sorted_players.into_iter().map(to_player).collect(),
+    }
+}
+
+fn to_player(player: structs::player_list::GamePlayer) -> Player {
+    Player {
+        player_id: player.player_id.to_string(),
+        name: player.name,
+        platoon: player.platoon,
+        rank: player.rank as i64,
+        squad: None,
+        class: None,
+        score: None,
+        kills: None,
+        deaths: None,
+        ping: Some(player.latency),
+        join_time: Some(player.join_time),
+    }
+}
+
+fn seeder_teams(
+    seeder_result: structs::seeder_player_list::SeederPlayerList,
+    result: &structs::player_list::PlayerList,
+) -> Vec<Team> {
+    let mut players = HashMap::new();
+    for team in &result.teams {
+        for player in &team.players {
+            players.insert(player.player_id, player);
+        }
+    }
+
+    seeder_result
+        .teams
+        .into_iter()
+        .map(|team| {
+            let mut sorted_players = team.players;
+            sorted_players.sort_by_key(|item| item.index);
+
+            Team {
+                name: team.name,
+                image: Some(team.image),
+                score: Some(team.score),
+                players: sorted_players
+                    .into_iter()
+                    .map(|seeder_player| {
+                        let player = players.get(&seeder_player.player_id);
+                        Player {
+                            player_id: seeder_player.player_id.to_string(),
+                            name: seeder_player.name,
+                            platoon: seeder_player.platoon.name,
+                            rank: seeder_player.rank as i64,
+                            squad: Some(seeder_player.squad_name),
+                            class: Some(seeder_player.player_class.class_name.unwrap_or_default()),
+                            score: Some(seeder_player.score as i64),
+                            kills: Some(seeder_player.kills as i64),
+                            deaths: Some(seeder_player.deaths as i64),
+                            ping: player.map(|player| player.latency),
+                            join_time: player.map(|player| player.join_time),
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
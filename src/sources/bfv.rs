@@ -0,0 +1,129 @@
+use anyhow::Result;
+
+use crate::structs::{
+    self,
+    snapshot::{Player, Server, ServerDetails, Snapshot, SourceUpdate, Team},
+};
+
+pub struct BfvSource;
+
+#[serenity::async_trait]
+impl super::GameSource for BfvSource {
+    async fn fetch(
+        &self,
+        api_url: &str,
+        server: &structs::config::ServerConfig,
+        client: &reqwest::Client,
+    ) -> Result<Snapshot> {
+        let result = match request_player_list(api_url, server, client).await {
+            Ok(result) => result,
+            Err(e) => anyhow::bail!("Couldn't get bfv playerlist {:#?}", e),
+        };
+
+        // only used for the server details, the playerlist is enough to show the tables
+        let detailed_result = request_detailed_server(api_url, server, client).await.ok();
+
+        Ok(to_snapshot(result, detailed_result))
+    }
+}
+
+pub async fn request_player_list(
+    api_url: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::bfv_player_list::BfvPlayerList> {
+    let mut url = super::endpoint(api_url, "bfv/players/")?;
+    super::server_query(&mut url, server);
+
+    super::retry::DEFAULT_POLICY
+        .get_json::<structs::bfv_player_list::BfvPlayerList>(client, url)
+        .await
+}
+
+pub async fn request_detailed_server(
+    api_url: &str,
+    server: &structs::config::ServerConfig,
+    client: &reqwest::Client,
+) -> Result<structs::bfv_player_list::BfvDetailedServerInfo> {
+    let mut url = super::endpoint(api_url, "bfv/detailedserver/")?;
+    super::server_query(&mut url, server);
+
+    super::retry::DEFAULT_POLICY
+        .get_json::<structs::bfv_player_list::BfvDetailedServerInfo>(client, url)
+        .await
+}
+
+fn to_snapshot(
+    result: structs::bfv_player_list::BfvPlayerList,
+    detailed_result: Option<structs::bfv_player_list::BfvDetailedServerInfo>,
+) -> Snapshot {
+    let teams = result
+        .teams
+        .into_iter()
+        .map(|team| {
+            let mut sorted_players = team.players;
+            sorted_players.sort_by_key(|item| item.slot);
+
+            Team {
+                name: team.name,
+                image: Some(team.image),
+                score: None,
+                players: sorted_players.into_iter().map(to_player).collect(),
+            }
+        })
+        .collect();
+
+    let (server_id, favorites, max_players) = match detailed_result {
+        Some(detailed) => (
+            detailed.game_id,
+            detailed.favorites,
+            detailed.max_player_amount,
+        ),
+        None => (None, None, None),
+    };
+
+    Snapshot {
+        server: Server {
+            name: result.serverinfo.name,
+            server_id,
+            current_map: Some(result.serverinfo.level),
+            mode: Some(result.serverinfo.mode),
+            favorites,
+            max_players,
+            in_queue: Some(result.que.len() as u64),
+            loading: Some(result.loading.len() as u64),
+        },
+        details: Some(ServerDetails {
+            description: result.serverinfo.description,
+            region: result.serverinfo.region,
+            country: result.serverinfo.country,
+            owner: result.serverinfo.owner,
+            maps: result.serverinfo.maps.unwrap_or_default(),
+            settings: result.serverinfo.settings.unwrap_or_default(),
+        }),
+        teams,
+        queue: result.que.into_iter().map(to_player).collect(),
+        loading: result.loading.into_iter().map(to_player).collect(),
+        chat: vec![],
+        updates: vec![SourceUpdate {
+            label: "Gametools update".into(),
+            timestamp: Some(result.update_timestamp),
+        }],
+    }
+}
+
+fn to_player(player: structs::bfv_player_list::BfvPlayer) -> Player {
+    Player {
+        player_id: player.player_id.to_string(),
+        name: player.name,
+        platoon: player.platoon,
+        rank: player.rank as i64,
+        squad: None,
+        class: None,
+        score: None,
+        kills: None,
+        deaths: None,
+        ping: Some(player.latency),
+        join_time: Some(player.join_time),
+    }
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use reqwest::Url;
+
+use crate::structs::{self, config::Games};
+pub mod battlelog;
+pub mod bf1;
+pub mod bfv;
+pub mod retry;
+
+/// A game api the bot can follow servers on
+#[serenity::async_trait]
+pub trait GameSource: Send + Sync {
+    /// gather the current state of the server in the game independent model
+    async fn fetch(
+        &self,
+        api_url: &str,
+        server: &structs::config::ServerConfig,
+        client: &reqwest::Client,
+    ) -> Result<structs::snapshot::Snapshot>;
+}
+
+/// url of an endpoint relative to the configured api url
+pub fn endpoint(api_url: &str, path: &str) -> Result<Url> {
+    let mut base = Url::parse(api_url)?;
+    // without the slash join would replace the last part of the path
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
+    }
+    Ok(base.join(path)?)
+}
+
+/// look the server up by its gameId if configured, falls back to searching by name
+pub fn server_query(url: &mut Url, server: &structs::config::ServerConfig) {
+    match &server.server_id {
+        Some(server_id) => url.query_pairs_mut().append_pair("gameid", server_id),
+        None => url
+            .query_pairs_mut()
+            .append_pair("name", &server.server_name),
+    };
+}
+
+pub fn for_game(game: &Games) -> Box<dyn GameSource> {
+    match game {
+        Games::Bf1 => Box::new(bf1::Bf1Source),
+        Games::Bf4 => Box::new(battlelog::BattlelogSource { game: "bf4" }),
+        Games::Bf3 => Box::new(battlelog::BattlelogSource { game: "bf3" }),
+        Games::Bfh => Box::new(battlelog::BattlelogSource { game: "bfh" }),
+        Games::Bfv => Box::new(bfv::BfvSource),
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use rand::Rng;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+/// time a single request may take, a stalled connection counts as a failed attempt
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often and how fast requests to the gametools api are retried
+pub struct RetryPolicy {
+    pub attempts: u32,
+    /// delay before the first retry, doubled for every retry after that
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+    /// random delay of up to this amount added to every backoff
+    pub jitter: Duration,
+    pub retry_statuses: &'static [StatusCode],
+}
+
+pub const DEFAULT_POLICY: RetryPolicy = RetryPolicy {
+    attempts: 3,
+    backoff: Duration::from_millis(500),
+    max_backoff: Duration::from_secs(5),
+    jitter: Duration::from_millis(250),
+    retry_statuses: &[
+        StatusCode::REQUEST_TIMEOUT,
+        StatusCode::TOO_MANY_REQUESTS,
+        StatusCode::INTERNAL_SERVER_ERROR,
+        StatusCode::BAD_GATEWAY,
+        StatusCode::SERVICE_UNAVAILABLE,
+        StatusCode::GATEWAY_TIMEOUT,
+    ],
+};
+
+impl RetryPolicy {
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+        let jitter = rand::thread_rng().gen_range(0..=self.jitter.as_millis() as u64);
+        backoff + Duration::from_millis(jitter)
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        client: &reqwest::Client,
+        url: Url,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            let start = Instant::now();
+            let error = match client.get(url.clone()).send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.json::<T>().await?)
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    log::warn!(
+                        "Request to {} failed with status {} after {}ms (attempt {}/{})",
+                        url,
+                        status,
+                        start.elapsed().as_millis(),
+                        attempt,
+                        self.attempts
+                    );
+                    if !self.retry_statuses.contains(&status) {
+                        anyhow::bail!("{} returned {}", url, status);
+                    }
+                    anyhow::anyhow!("{} returned {}", url, status)
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Request to {} failed with status {} after {}ms (attempt {}/{}): {}",
+                        url,
+                        e.status()
+                            .map(|status| status.to_string())
+                            .unwrap_or_else(|| "none".into()),
+                        start.elapsed().as_millis(),
+                        attempt,
+                        self.attempts,
+                        e
+                    );
+                    e.into()
+                }
+            };
+
+            if attempt >= self.attempts {
+                return Err(error);
+            }
+            tokio::time::sleep(self.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use serenity::{
+    client::Context,
+    model::{gateway::Activity, prelude::ChannelId},
+};
+
+use crate::structs::{config::StatusChannel, snapshot::Snapshot};
+
+/// discord only allows a channel to be renamed twice every ten minutes
+const MAX_RENAMES: usize = 2;
+const RENAME_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+/// fill in a status template, unknown values are shown as `?`
+///
+/// `{players}`, `{max_players}`, `{queue}`, `{map}`, `{mode}` and `{server}`
+/// are replaced with the values of the snapshot
+pub fn render(template: &str, snapshot: &Snapshot) -> String {
+    let unknown = || "?".to_string();
+    let players: usize = snapshot.teams.iter().map(|team| team.players.len()).sum();
+
+    template
+        .replace("{players}", &players.to_string())
+        .replace(
+            "{max_players}",
+            &snapshot
+                .server
+                .max_players
+                .map_or_else(unknown, |max| max.to_string()),
+        )
+        .replace(
+            "{queue}",
+            &snapshot
+                .server
+                .in_queue
+                .map_or_else(unknown, |queue| queue.to_string()),
+        )
+        .replace(
+            "{map}",
+            &snapshot.server.current_map.clone().unwrap_or_else(unknown),
+        )
+        .replace(
+            "{mode}",
+            &snapshot.server.mode.clone().unwrap_or_else(unknown),
+        )
+        .replace("{server}", &snapshot.server.name)
+}
+
+#[derive(Default)]
+pub struct RenameState {
+    name: Option<String>,
+    renames: VecDeque<Instant>,
+}
+
+/// rename the status channel if the name changed and the rate limit allows it,
+/// otherwise it's tried again on the next update
+pub async fn rename_channel(
+    ctx: &Context,
+    status_channel: &StatusChannel,
+    snapshot: &Snapshot,
+    state: &mut RenameState,
+) {
+    let name = render(&status_channel.template, snapshot);
+    if state.name.as_ref() == Some(&name) {
+        return;
+    }
+
+    while let Some(renamed) = state.renames.front() {
+        if renamed.elapsed() < RENAME_PERIOD {
+            break;
+        }
+        state.renames.pop_front();
+    }
+    if state.renames.len() >= MAX_RENAMES {
+        return;
+    }
+
+    state.renames.push_back(Instant::now());
+    match ChannelId(status_channel.channel)
+        .edit(&ctx.http, |channel| channel.name(&name))
+        .await
+    {
+        Ok(_) => state.name = Some(name),
+        Err(e) => log::error!("Failed to rename status channel: {:#?}", e),
+    }
+}
+
+/// show the server status as the bot's activity, only updated when it changed
+pub async fn set_presence(
+    ctx: &Context,
+    template: &str,
+    snapshot: &Snapshot,
+    current: &mut Option<String>,
+) {
+    let status = render(template, snapshot);
+    if current.as_ref() != Some(&status) {
+        ctx.set_activity(Activity::playing(&status)).await;
+        *current = Some(status);
+    }
+}
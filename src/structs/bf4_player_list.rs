@@ -19,13 +19,20 @@ pub struct ScoreTeamList {
     pub teamid: String,
 }
 
+/// detailed server response of bf4, bf3 and bfh
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DetailedServerInfo {
     pub prefix: String,
+    #[serde(rename = "gameId")]
+    pub game_id: Option<String>,
     #[serde(rename = "currentMap")]
     pub current_map: String,
     pub mode: String,
     pub favorites: String,
+    #[serde(rename = "maxPlayerAmount")]
+    pub max_player_amount: Option<u64>,
+    #[serde(rename = "inQueue")]
+    pub in_queue: Option<u64>,
     pub teams: Option<Vec<ScoreTeamList>>,
     pub players: Option<Vec<ScoreServerPlayer>>,
 }
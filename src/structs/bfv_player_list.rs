@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BfvServerInfo {
+    pub name: String,
+    pub description: String,
+    pub region: String,
+    pub country: String,
+    pub level: String,
+    pub mode: String,
+    pub maps: Option<Vec<String>>,
+    pub owner: String,
+    pub settings: Option<Vec<String>>,
+    pub servertype: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BfvPlayer {
+    pub join_time: i64,
+    pub latency: u64,
+    pub name: String,
+    pub platoon: String,
+    pub player_id: u64,
+    pub rank: u64,
+    pub slot: u64,
+    pub user_id: u64,
+    pub platform: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BfvTeam {
+    pub teamid: String,
+    pub players: Vec<BfvPlayer>,
+    pub image: String,
+    pub key: String,
+    pub name: String,
+    pub faction: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BfvPlayerList {
+    pub serverinfo: BfvServerInfo,
+    pub teams: Vec<BfvTeam>,
+    pub que: Vec<BfvPlayer>,
+    pub loading: Vec<BfvPlayer>,
+    pub update_timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BfvDetailedServerInfo {
+    pub prefix: String,
+    #[serde(rename = "gameId")]
+    pub game_id: Option<String>,
+    #[serde(rename = "currentMap")]
+    pub current_map: String,
+    pub mode: String,
+    pub favorites: Option<String>,
+    #[serde(rename = "maxPlayerAmount")]
+    pub max_player_amount: Option<u64>,
+}
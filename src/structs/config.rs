@@ -1,39 +1,295 @@
-use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr, sync::Arc};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serenity::prelude::RwLock;
+
+pub const CONFIG_PATH: &str = "config.txt";
+
+/// config shared between the update loop and the admin commands
+pub type SharedConfig = Arc<RwLock<SenderConfig>>;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SenderConfig {
-    pub server_name: String,
-    pub game: Games,
     pub token: String,
-    pub channel: u64,
-    pub messages: Vec<u64>,
+    /// base url of the gametools api, can point to a caching proxy
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+    /// sqlite file every snapshot is recorded in, no history is kept if unset
+    pub history_database: Option<String>,
+    /// days of history to keep, 0 keeps everything
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: u64,
+    /// template for the bot's status, see `status::render`
+    pub presence: Option<String>,
+    /// server shown in the presence, the first server if unset
+    pub presence_server: Option<String>,
+    /// single server of configs from before `servers`, see `SenderConfig::migrate`
+    #[serde(default, skip_serializing)]
+    pub server_name: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub game: Option<Games>,
+    #[serde(default, skip_serializing)]
+    pub channel: Option<u64>,
+    #[serde(default, skip_serializing)]
+    pub messages: Option<Vec<u64>>,
+    #[serde(default)]
+    pub servers: Vec<ServerConfig>,
+}
+
+fn default_api_url() -> String {
+    "https://api.gametools.network/".into()
+}
+
+fn default_history_retention_days() -> u64 {
+    30
 }
 
 /// `SeederConfig` implements `Default`
 impl ::std::default::Default for SenderConfig {
     fn default() -> Self {
         Self {
-            server_name: "".into(),
             token: "".into(),
-            channel: 0,
-            messages: vec![],
-            game: Games::from("bf1"),
+            api_url: default_api_url(),
+            history_database: None,
+            history_retention_days: default_history_retention_days(),
+            presence: None,
+            presence_server: None,
+            server_name: None,
+            game: None,
+            channel: None,
+            messages: None,
+            servers: vec![],
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+impl SenderConfig {
+    /// the server the bot's status shows
+    pub fn presence_server(&self) -> Option<&str> {
+        match &self.presence_server {
+            Some(server_name) => Some(server_name),
+            None => self
+                .servers
+                .first()
+                .map(|server| server.server_name.as_str()),
+        }
+    }
+
+    /// move the server of a config from before `servers` into the first
+    /// `servers` entry, keeping its messages, returns if there was one
+    pub fn migrate(&mut self) -> bool {
+        let server_name = match self.server_name.take() {
+            Some(server_name) => server_name,
+            None => return false,
+        };
+        self.servers.insert(
+            0,
+            ServerConfig {
+                server_name,
+                server_id: None,
+                game: self.game.take().unwrap_or(Games::Bf1),
+                channel: self.channel.take().unwrap_or_default(),
+                output: Output::default(),
+                details: false,
+                chat_channel: None,
+                join_log_channel: None,
+                round_summary_channel: None,
+                messages: self.messages.take().unwrap_or_default(),
+                pending_reset: None,
+                status_channel: None,
+                chat_alerts: None,
+            },
+        );
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub server_name: String,
+    /// gameId the api returns for the server, used instead of the name if set
+    pub server_id: Option<String>,
+    pub game: Games,
+    pub channel: u64,
+    /// how the playerlist is posted
+    #[serde(default)]
+    pub output: Output,
+    /// post the description, rotation and settings of the server
+    #[serde(default)]
+    pub details: bool,
+    /// channel the ingame chat is relayed to
+    pub chat_channel: Option<u64>,
+    /// channel players joining and leaving are posted in
+    pub join_log_channel: Option<u64>,
+    /// channel the final scoreboard of every round is posted in
+    pub round_summary_channel: Option<u64>,
+    pub messages: Vec<u64>,
+    /// set by the admin commands, the messages are removed from this channel
+    /// and posted again by the next update
+    pub pending_reset: Option<u64>,
+    // tables have to be placed after the values for toml
+    pub status_channel: Option<StatusChannel>,
+    pub chat_alerts: Option<ChatAlerts>,
+}
+
+/// ping moderators when the ingame chat matches one of the rules
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChatAlerts {
+    pub channel: u64,
+    /// role pinged for every alert
+    pub role: u64,
+    pub rules: Vec<AlertRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct AlertRule {
+    /// case-insensitive regex, plain keywords work as well
+    pub pattern: String,
+    /// seconds before this rule alerts again
+    #[serde(default)]
+    pub cooldown: u64,
+}
+
+/// channel renamed to show the server status
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct StatusChannel {
+    pub channel: u64,
+    /// see `status::render`
+    pub template: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// tables in code blocks
+    #[default]
+    Text,
+    Embed,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
 pub enum Games {
     Bf4,
     Bf1,
+    Bfv,
+    Bf3,
+    Bfh,
 }
 
 impl Games {
-    pub fn from(input: &str) -> Games {
-        match input {
-            "bf4" => Games::Bf4,
-            "bf1" => Games::Bf1,
-            _ => Games::Bf1,
+    pub const ALL: [Games; 5] = [Games::Bf4, Games::Bf1, Games::Bfv, Games::Bf3, Games::Bfh];
+}
+
+impl fmt::Display for Games {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Games::Bf4 => "bf4",
+            Games::Bf1 => "bf1",
+            Games::Bfv => "bfv",
+            Games::Bf3 => "bf3",
+            Games::Bfh => "bfh",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Games {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let name: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_' && *c != '-')
+            .collect();
+        Ok(match name.to_lowercase().as_str() {
+            "bf4" | "battlefield4" => Games::Bf4,
+            "bf1" | "battlefield1" => Games::Bf1,
+            "bfv" | "bf5" | "battlefieldv" | "battlefield5" => Games::Bfv,
+            "bf3" | "battlefield3" => Games::Bf3,
+            "bfh" | "battlefieldhardline" | "hardline" => Games::Bfh,
+            _ => anyhow::bail!(
+                "unknown game \"{}\", valid games are: {}",
+                input,
+                Games::ALL.map(|game| game.to_string()).join(", ")
+            ),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Games {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        input.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_single_server_config() {
+        let path = std::env::temp_dir().join("discord_playerlist_legacy_config.txt");
+        std::fs::write(
+            &path,
+            "server_name = '[BoB]#1 EU All CQ'
+token = 'SECRET_DISCORD_TOKEN'
+channel = 106456457126165306
+messages = [1, 2]
+game = 'Bf4'
+",
+        )
+        .unwrap();
+        let mut cfg: SenderConfig = confy::load_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(cfg.migrate());
+        assert_eq!(cfg.servers.len(), 1);
+        let server = &cfg.servers[0];
+        assert_eq!(server.server_name, "[BoB]#1 EU All CQ");
+        assert_eq!(server.game, Games::Bf4);
+        assert_eq!(server.channel, 106456457126165306);
+        assert_eq!(server.messages, vec![1, 2]);
+        assert!(!cfg.migrate());
+    }
+
+    #[test]
+    fn parses_game_names() {
+        let cases = [
+            ("bf4", Games::Bf4),
+            ("Bf4", Games::Bf4),
+            ("Battlefield 4", Games::Bf4),
+            ("bf1", Games::Bf1),
+            ("battlefield_1", Games::Bf1),
+            ("BFV", Games::Bfv),
+            ("bf5", Games::Bfv),
+            ("battlefield-v", Games::Bfv),
+            (" bf3 ", Games::Bf3),
+            ("bfh", Games::Bfh),
+            ("Hardline", Games::Bfh),
+            ("Battlefield Hardline", Games::Bfh),
+        ];
+        for (input, game) in cases {
+            assert_eq!(input.parse::<Games>().unwrap(), game, "{}", input);
+        }
+    }
+
+    #[test]
+    fn display_names_parse_back() {
+        for game in Games::ALL {
+            assert_eq!(game.to_string().parse::<Games>().unwrap(), game);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_games() {
+        for input in ["", "bf2042", "battlefield"] {
+            let error = input.parse::<Games>().unwrap_err().to_string();
+            assert!(
+                error.contains("valid games are: bf4, bf1, bfv, bf3, bfh"),
+                "{}",
+                error
+            );
         }
     }
 }
@@ -1,4 +1,6 @@
 pub mod bf4_player_list;
+pub mod bfv_player_list;
 pub mod config;
 pub mod player_list;
 pub mod seeder_player_list;
+pub mod snapshot;
@@ -14,6 +14,16 @@ pub struct ServerInfo {
     pub servertype: String,
 }
 
+/// the values of bf1/detailedserver the playerlist doesn't have
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetailedServerInfo {
+    #[serde(rename = "gameId")]
+    pub game_id: Option<String>,
+    pub favorites: Option<String>,
+    #[serde(rename = "maxPlayerAmount")]
+    pub max_player_amount: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GamePlayer {
     pub join_time: i64,
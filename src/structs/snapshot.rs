@@ -0,0 +1,140 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serenity::prelude::RwLock;
+
+/// latest snapshot of every followed server, by server_name
+pub type SnapshotCache = Arc<RwLock<HashMap<String, Snapshot>>>;
+
+/// Game independent view of a server, produced by a `GameSource`
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub server: Server,
+    /// description, rotation and settings, if the game has them
+    pub details: Option<ServerDetails>,
+    pub teams: Vec<Team>,
+    /// players waiting to join, empty if the game doesn't list them
+    pub queue: Vec<Player>,
+    pub loading: Vec<Player>,
+    /// recent ingame chat, empty if the game doesn't provide it
+    pub chat: Vec<ChatMessage>,
+    /// api updates shown in the title, `None` if that source isn't running
+    pub updates: Vec<SourceUpdate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Server {
+    pub name: String,
+    /// id the server can be looked up with, if the api returned it
+    pub server_id: Option<String>,
+    pub current_map: Option<String>,
+    pub mode: Option<String>,
+    pub favorites: Option<String>,
+    pub max_players: Option<u64>,
+    pub in_queue: Option<u64>,
+    pub loading: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerDetails {
+    pub description: String,
+    pub region: String,
+    pub country: String,
+    pub owner: String,
+    pub maps: Vec<String>,
+    pub settings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Team {
+    pub name: String,
+    /// faction image
+    pub image: Option<String>,
+    pub score: Option<i64>,
+    pub players: Vec<Player>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub player_id: String,
+    pub name: String,
+    pub platoon: String,
+    pub rank: i64,
+    pub squad: Option<String>,
+    pub class: Option<String>,
+    pub score: Option<i64>,
+    pub kills: Option<i64>,
+    pub deaths: Option<i64>,
+    /// latency in ms
+    pub ping: Option<u64>,
+    /// join time in microseconds since epoch
+    pub join_time: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChatMessage {
+    pub timestamp: String,
+    pub sender: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceUpdate {
+    pub label: String,
+    pub timestamp: Option<i64>,
+}
+
+impl Player {
+    /// name with platoon tag in front if the player has one
+    pub fn display_name(&self) -> String {
+        if self.platoon.is_empty() {
+            self.name.clone()
+        } else {
+            format!("[{}]{}", self.platoon, self.name)
+        }
+    }
+}
+
+#[cfg(test)]
+impl Snapshot {
+    /// server without players or chat to build test snapshots from
+    pub fn empty(map: &str, mode: &str) -> Self {
+        Self {
+            server: Server {
+                name: "Test server".into(),
+                server_id: None,
+                current_map: Some(map.into()),
+                mode: Some(mode.into()),
+                favorites: None,
+                max_players: None,
+                in_queue: None,
+                loading: None,
+            },
+            details: None,
+            teams: vec![],
+            queue: vec![],
+            loading: vec![],
+            chat: vec![],
+            updates: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+impl Player {
+    /// player with only a name, also used as the player_id
+    pub fn named(name: &str) -> Self {
+        Self {
+            player_id: name.into(),
+            name: name.into(),
+            platoon: "".into(),
+            rank: 0,
+            squad: None,
+            class: None,
+            score: None,
+            kills: None,
+            deaths: None,
+            ping: None,
+            join_time: None,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use serenity::{builder::CreateEmbed, model::Timestamp};
+
+use crate::{round, structs::snapshot::Snapshot, to_table};
+
+/// discord's limit on the length of an embed description
+const DESCRIPTION_LIMIT: usize = 4096;
+/// discord's limit on the length of an embed field
+const FIELD_LIMIT: usize = 1024;
+
+/// `with_details` adds the server details below the server info
+pub fn to_embeds(snapshot: &Snapshot, with_details: bool) -> Vec<CreateEmbed> {
+    let mut embeds = vec![server_embed(snapshot)];
+    if with_details {
+        embeds.extend(details_embed(snapshot));
+    }
+
+    for (team, table) in snapshot
+        .teams
+        .iter()
+        .zip(to_table::team_tables(snapshot, false))
+    {
+        let heading = to_table::team_heading(team);
+        for (index, description) in to_table::split_table(&table, 1, DESCRIPTION_LIMIT)
+            .into_iter()
+            .enumerate()
+        {
+            let mut embed = CreateEmbed::default();
+            embed.title(match index {
+                0 => heading.clone(),
+                _ => format!("{} (continued)", heading),
+            });
+            embed.description(description);
+            if let Some(image) = team.image.as_ref().filter(|image| !image.is_empty()) {
+                embed.thumbnail(image);
+            }
+            embeds.push(embed);
+        }
+    }
+
+    if let Some(table) = to_table::waiting_table(snapshot, false) {
+        for description in to_table::split_table(&table, 1, DESCRIPTION_LIMIT) {
+            let mut embed = CreateEmbed::default();
+            embed.title("Queue and loading").description(description);
+            embeds.push(embed);
+        }
+    }
+
+    if snapshot.teams.is_empty() {
+        let mut embed = CreateEmbed::default();
+        embed.title("Players").description("This server is empty");
+        embeds.push(embed);
+    }
+
+    embeds
+}
+
+/// cut off values that don't fit in an embed field
+fn field_value(lines: Vec<String>) -> String {
+    let mut value = String::new();
+    for line in lines {
+        if value.chars().count() + line.chars().count() + 1 > FIELD_LIMIT {
+            break;
+        }
+        value.push_str(&line);
+        value.push('\n');
+    }
+    value
+}
+
+/// map, mode, team scores and the best players of a finished round
+pub fn round_summary(snapshot: &Snapshot) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed.title(format!("{} round summary", snapshot.server.name));
+
+    if let Some(current_map) = &snapshot.server.current_map {
+        embed.field("Map", current_map, true);
+    }
+    if let Some(mode) = &snapshot.server.mode {
+        embed.field("Mode", mode, true);
+    }
+    for team in &snapshot.teams {
+        embed.field(
+            &team.name,
+            match team.score {
+                Some(score) => score.to_string(),
+                None => format!("{} players", team.players.len()),
+            },
+            true,
+        );
+    }
+
+    let top_score: Vec<String> = round::top_score(snapshot)
+        .into_iter()
+        .map(|(player, team)| {
+            format!(
+                "{} ({}) - {}",
+                player.display_name(),
+                team.name,
+                player.score.unwrap_or_default()
+            )
+        })
+        .collect();
+    if !top_score.is_empty() {
+        embed.field("Top score", field_value(top_score), false);
+    }
+    let top_kd: Vec<String> = round::top_kd(snapshot)
+        .into_iter()
+        .map(|(player, team)| {
+            format!(
+                "{} ({}) - {:.2} ({}/{})",
+                player.display_name(),
+                team.name,
+                round::kd(player).unwrap_or_default(),
+                player.kills.unwrap_or_default(),
+                player.deaths.unwrap_or_default()
+            )
+        })
+        .collect();
+    if !top_kd.is_empty() {
+        embed.field("Top K/D", field_value(top_kd), false);
+    }
+
+    embed.timestamp(Timestamp::now());
+    embed
+}
+
+fn details_embed(snapshot: &Snapshot) -> Option<CreateEmbed> {
+    let details = snapshot.details.as_ref()?;
+
+    let mut embed = CreateEmbed::default();
+    embed.title("Server details");
+    if !details.description.is_empty() {
+        embed.description(&details.description);
+    }
+    embed.field(
+        "Region",
+        format!("{} - {}", details.region, details.country),
+        true,
+    );
+    embed.field("Owner", &details.owner, true);
+
+    let maps: Vec<String> = details
+        .maps
+        .iter()
+        .map(|map| {
+            if snapshot.server.current_map.as_ref() == Some(map) {
+                format!("**{}**", map)
+            } else {
+                map.clone()
+            }
+        })
+        .collect();
+    if !maps.is_empty() {
+        embed.field("Rotation", field_value(maps), false);
+    }
+    if !details.settings.is_empty() {
+        embed.field("Settings", field_value(details.settings.clone()), false);
+    }
+    Some(embed)
+}
+
+fn server_embed(snapshot: &Snapshot) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed.title(format!("{} playerlist", snapshot.server.name));
+
+    if let Some(current_map) = &snapshot.server.current_map {
+        embed.field("Map", current_map, true);
+    }
+    if let Some(mode) = &snapshot.server.mode {
+        embed.field("Mode", mode, true);
+    }
+    let players: usize = snapshot.teams.iter().map(|team| team.players.len()).sum();
+    embed.field("Players", players, true);
+    if let Some(favorites) = &snapshot.server.favorites {
+        embed.field("Favorites", favorites, true);
+    }
+    if let Some(in_queue) = snapshot.server.in_queue {
+        embed.field("Queue", in_queue, true);
+    }
+    if let Some(loading) = snapshot.server.loading {
+        embed.field("Loading", loading, true);
+    }
+    for update in &snapshot.updates {
+        embed.field(
+            &update.label,
+            match update.timestamp {
+                Some(timestamp) => format!("<t:{}:T>", timestamp),
+                None => "Not running".into(),
+            },
+            true,
+        );
+    }
+
+    embed.footer(|f| f.text("Last bot update"));
+    embed.timestamp(Timestamp::now());
+    embed
+}
@@ -1,44 +1,466 @@
-use crate::structs;
+use crate::{
+    messages::MESSAGE_LIMIT,
+    round,
+    structs::snapshot::{Player, Snapshot, Team},
+};
 
 use chrono::Utc;
-use tabular::row;
-pub mod bf4_player_list;
-pub mod player_list;
-pub mod seeder_player_list;
-
-async fn title_table(
-    result: &structs::player_list::PlayerList,
-    seeder_player_list: Option<&structs::seeder_player_list::SeederPlayerList>,
-) -> String {
-    let timestamp = chrono::DateTime::<Utc>::from_utc(
-        match chrono::NaiveDateTime::from_timestamp_opt(result.update_timestamp, 0) {
-            Some(naive_time) => naive_time,
-            None => chrono::NaiveDateTime::default(),
-        },
-        Utc,
-    );
+use tabular::Row;
+
+/// table heading and column titles, repeated in every message of a table
+const HEADER_LINES: usize = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Squad,
+    Class,
+    Rank,
+    Name,
+    Score,
+    Kd,
+    Ping,
+    Playtime,
+}
+
+impl Column {
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Squad => "Squad",
+            Column::Class => "Class",
+            Column::Rank => "Rank",
+            Column::Name => "Name",
+            Column::Score => "Score",
+            Column::Kd => "KD",
+            Column::Ping => "Ping",
+            Column::Playtime => "Playtime",
+        }
+    }
 
-    let seeder_time = match seeder_player_list {
-        Some(seeder_info) => chrono::DateTime::<Utc>::from_utc(
-            match chrono::NaiveDateTime::from_timestamp_opt(seeder_info.update_timestamp, 0) {
-                Some(naive_time) => naive_time,
-                None => chrono::NaiveDateTime::default(),
+    fn value(&self, player: &Player) -> String {
+        match self {
+            Column::Squad => player.squad.clone().unwrap_or_default(),
+            Column::Class => player.class.clone().unwrap_or_default(),
+            Column::Rank => player.rank.to_string(),
+            Column::Name => player.display_name(),
+            Column::Score => match player.score {
+                Some(score) => score.to_string(),
+                None => "?".into(),
             },
-            Utc,
+            Column::Kd => match (player.kills, player.deaths) {
+                (Some(kills), Some(deaths)) => format!("{}/{}", kills, deaths),
+                _ => "?".into(),
+            },
+            Column::Ping => match player.ping {
+                Some(ping) => format!("{}ms", ping),
+                None => "?".into(),
+            },
+            Column::Playtime => match player.join_time {
+                Some(join_time) => playtime(join_time),
+                None => "".into(),
+            },
+        }
+    }
+}
+
+/// only show the columns the game gave information for
+fn columns(snapshot: &Snapshot) -> Vec<Column> {
+    let players: Vec<&Player> = snapshot
+        .teams
+        .iter()
+        .flat_map(|team| team.players.iter())
+        .collect();
+    let has = |check: fn(&Player) -> bool| players.iter().any(|player| check(player));
+
+    let mut columns = vec![];
+    if has(|player| player.squad.is_some()) {
+        columns.push(Column::Squad);
+    }
+    if has(|player| player.class.is_some()) {
+        columns.push(Column::Class);
+    }
+    columns.push(Column::Rank);
+    columns.push(Column::Name);
+    if has(|player| player.score.is_some()) {
+        columns.push(Column::Score);
+    }
+    if has(|player| player.kills.is_some()) {
+        columns.push(Column::Kd);
+    }
+    if has(|player| player.ping.is_some()) {
+        columns.push(Column::Ping);
+    }
+    if has(|player| player.join_time.is_some()) {
+        columns.push(Column::Playtime);
+    }
+    columns
+}
+
+/// time since the player joined, `join_time` in microseconds
+pub fn playtime(join_time: i64) -> String {
+    let mut f = timeago::Formatter::new();
+    f.ago("");
+    f.convert_chrono(to_datetime(join_time / 1000000), chrono::Utc::now())
+}
+
+fn to_datetime(timestamp: i64) -> chrono::DateTime<Utc> {
+    chrono::DateTime::<Utc>::from_utc(
+        chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0).unwrap_or_default(),
+        Utc,
+    )
+}
+
+fn row_spec(column_count: usize) -> String {
+    vec!["{:<}"; column_count].join("  ")
+}
+
+fn code_block(lines: &[&str]) -> String {
+    format!("```\n{}\n```", lines.join("\n"))
+}
+
+/// split a table into as few code block messages as fit discord's limit,
+/// without cutting rows in half
+pub fn to_messages(table: &str) -> Vec<String> {
+    split_table(table, HEADER_LINES, MESSAGE_LIMIT)
+}
+
+/// split a table into code blocks of at most `limit` characters, repeating
+/// the first `header_lines` lines in every block
+pub fn split_table(table: &str, header_lines: usize, limit: usize) -> Vec<String> {
+    let lines: Vec<&str> = table.trim_end().split('\n').collect();
+    let (header, rows) = lines.split_at(header_lines.min(lines.len()));
+
+    let mut messages = vec![];
+    let mut current: Vec<&str> = header.to_vec();
+    for row in rows {
+        let mut next = current.clone();
+        next.push(row);
+        if current.len() > header.len() && code_block(&next).chars().count() > limit {
+            messages.push(code_block(&current));
+            current = header.to_vec();
+        }
+        current.push(row);
+    }
+    messages.push(code_block(&current));
+    messages
+}
+
+/// team name with the score if the game has one
+pub fn team_heading(team: &Team) -> String {
+    match team.score {
+        Some(score) => format!("{} - score: {}", team.name, score),
+        None => team.name.clone(),
+    }
+}
+
+/// a table for every team, `with_heading` adds the team name above the columns
+pub fn team_tables(snapshot: &Snapshot, with_heading: bool) -> Vec<String> {
+    let columns = columns(snapshot);
+
+    let mut teams: Vec<String> = vec![];
+    for team in &snapshot.teams {
+        let mut table = tabular::Table::new(&row_spec(columns.len()));
+        if with_heading {
+            table.add_heading(team_heading(team));
+        }
+        table.add_row(Row::from_cells(columns.iter().map(|column| column.title())));
+
+        if team.players.is_empty() {
+            table.add_row(Row::from_cells(columns.iter().map(|column| match column {
+                Column::Name => "This team is empty",
+                _ => "N/A",
+            })));
+        }
+
+        for player in &team.players {
+            table.add_row(Row::from_cells(
+                columns.iter().map(|column| column.value(player)),
+            ));
+        }
+
+        teams.push(table.to_string());
+    }
+    teams
+}
+
+/// title and team tables split into discord messages, `with_details` adds
+/// the server details below the title
+pub async fn to_all_messages(snapshot: &Snapshot, with_details: bool) -> Vec<String> {
+    let (title, tables) = to_tables(snapshot).await;
+    let mut contents = to_messages(&title);
+    if with_details {
+        if let Some(details) = details_table(snapshot) {
+            contents.extend(split_table(&details, 1, MESSAGE_LIMIT));
+        }
+    }
+    for content in tables.iter() {
+        contents.extend(to_messages(content));
+    }
+    contents
+}
+
+/// description, region, owner, map rotation and settings of the server
+pub fn details_table(snapshot: &Snapshot) -> Option<String> {
+    let details = snapshot.details.as_ref()?;
+
+    let mut table = tabular::Table::new("{:<}  {:<}")
+        .with_heading("Server details")
+        .with_row(
+            Row::new()
+                .with_cell("Region")
+                .with_cell(format!("{} - {}", details.region, details.country)),
         )
-        .format("%T %b %e %Y")
-        .to_string(),
-        None => "Not running".into(),
-    };
-
-    let table = tabular::Table::new("{:<}  {:<}  {:<}")
-        .with_heading(format!("{} playerlist", result.serverinfo.name))
-        .with_row(row!("Last bot update", "Gametools update", "Gather update"))
-        .with_row(row!(
-            chrono::Utc::now().format("%T %b %e %Y"),
-            timestamp.format("%T %b %e %Y"),
-            seeder_time
-        ));
+        .with_row(Row::new().with_cell("Owner").with_cell(&details.owner));
+    if !details.description.is_empty() {
+        table.add_row(
+            Row::new()
+                .with_cell("Description")
+                .with_cell(&details.description),
+        );
+    }
+
+    for (index, map) in details.maps.iter().enumerate() {
+        let current = snapshot.server.current_map.as_ref() == Some(map);
+        table.add_row(
+            Row::new()
+                .with_cell(if index == 0 { "Rotation" } else { "" })
+                .with_cell(if current {
+                    format!("> {}", map)
+                } else {
+                    format!("  {}", map)
+                }),
+        );
+    }
+    for (index, setting) in details.settings.iter().enumerate() {
+        table.add_row(
+            Row::new()
+                .with_cell(if index == 0 { "Settings" } else { "" })
+                .with_cell(setting),
+        );
+    }
+
+    Some(table.to_string())
+}
+
+/// players in the queue or still loading in, `None` if there aren't any
+pub fn waiting_table(snapshot: &Snapshot, with_heading: bool) -> Option<String> {
+    if snapshot.queue.is_empty() && snapshot.loading.is_empty() {
+        return None;
+    }
+
+    let mut table = tabular::Table::new("{:<}  {:<}  {:<}  {:<}");
+    if with_heading {
+        table.add_heading("Queue and loading");
+    }
+    table.add_row(Row::from_cells(["Status", "Rank", "Name", "Waiting"]));
+
+    let waiting = snapshot
+        .queue
+        .iter()
+        .map(|player| ("Queue", player))
+        .chain(snapshot.loading.iter().map(|player| ("Loading", player)));
+    for (status, player) in waiting {
+        table.add_row(
+            Row::new()
+                .with_cell(status)
+                .with_cell(player.rank)
+                .with_cell(player.display_name())
+                .with_cell(match player.join_time {
+                    Some(join_time) => playtime(join_time),
+                    None => "".into(),
+                }),
+        );
+    }
+
+    Some(table.to_string())
+}
+
+/// map, mode, team scores and the best players of a finished round
+pub fn round_summary(snapshot: &Snapshot) -> String {
+    let mut summary = tabular::Table::new("{:<}  {:<}")
+        .with_heading(format!("{} round summary", snapshot.server.name));
+    if let Some(current_map) = &snapshot.server.current_map {
+        summary.add_row(Row::new().with_cell("Map").with_cell(current_map));
+    }
+    if let Some(mode) = &snapshot.server.mode {
+        summary.add_row(Row::new().with_cell("Mode").with_cell(mode));
+    }
+    for team in &snapshot.teams {
+        summary.add_row(
+            Row::new()
+                .with_cell(&team.name)
+                .with_cell(match team.score {
+                    Some(score) => score.to_string(),
+                    None => format!("{} players", team.players.len()),
+                }),
+        );
+    }
+    let mut tables = vec![summary.to_string()];
+
+    let top_score = round::top_score(snapshot);
+    if !top_score.is_empty() {
+        let mut table = tabular::Table::new("{:<}  {:<}  {:<}")
+            .with_heading("Top score")
+            .with_row(Row::from_cells(["Name", "Team", "Score"]));
+        for (player, team) in top_score {
+            table.add_row(
+                Row::new()
+                    .with_cell(player.display_name())
+                    .with_cell(&team.name)
+                    .with_cell(player.score.unwrap_or_default()),
+            );
+        }
+        tables.push(table.to_string());
+    }
+
+    let top_kd = round::top_kd(snapshot);
+    if !top_kd.is_empty() {
+        let mut table = tabular::Table::new("{:<}  {:<}  {:<}  {:<}")
+            .with_heading("Top K/D")
+            .with_row(Row::from_cells(["Name", "Team", "KD", "Kills/Deaths"]));
+        for (player, team) in top_kd {
+            table.add_row(
+                Row::new()
+                    .with_cell(player.display_name())
+                    .with_cell(&team.name)
+                    .with_cell(format!("{:.2}", round::kd(player).unwrap_or_default()))
+                    .with_cell(Column::Kd.value(player)),
+            );
+        }
+        tables.push(table.to_string());
+    }
+
+    tables
+        .iter()
+        .map(|table| code_block(&[table.trim_end()]))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub async fn to_tables(snapshot: &Snapshot) -> (String, Vec<String>) {
+    let mut teams = team_tables(snapshot, true);
+
+    if teams.is_empty() {
+        let table = tabular::Table::new("{:<}")
+            .with_heading("Players")
+            .with_row(Row::new().with_cell("This server is empty"));
+        teams.push(table.to_string());
+    }
+    teams.extend(waiting_table(snapshot, true));
+
+    let title = title_table(snapshot).await;
+
+    (title, teams)
+}
+
+async fn title_table(snapshot: &Snapshot) -> String {
+    let mut titles = Row::new().with_cell("Last bot update");
+    let mut values = Row::new().with_cell(chrono::Utc::now().format("%T %b %e %Y"));
+
+    if let Some(current_map) = &snapshot.server.current_map {
+        titles.add_cell("Current map");
+        values.add_cell(match &snapshot.server.mode {
+            Some(mode) => format!("{} - {}", current_map, mode),
+            None => current_map.clone(),
+        });
+    }
+    if let Some(favorites) = &snapshot.server.favorites {
+        titles.add_cell("Favorites");
+        values.add_cell(favorites);
+    }
+    if let Some(in_queue) = snapshot.server.in_queue {
+        titles.add_cell("Queue");
+        values.add_cell(in_queue);
+    }
+    if let Some(loading) = snapshot.server.loading {
+        titles.add_cell("Loading");
+        values.add_cell(loading);
+    }
+    for update in &snapshot.updates {
+        titles.add_cell(&update.label);
+        values.add_cell(match update.timestamp {
+            Some(timestamp) => to_datetime(timestamp).format("%T %b %e %Y").to_string(),
+            None => "Not running".into(),
+        });
+    }
+
+    let table = tabular::Table::new(&row_spec(titles.len()))
+        .with_heading(format!("{} playerlist", snapshot.server.name))
+        .with_row(titles)
+        .with_row(values);
 
     table.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_lines(block: &str) -> Vec<&str> {
+        block
+            .strip_prefix("```\n")
+            .and_then(|block| block.strip_suffix("\n```"))
+            .unwrap()
+            .split('\n')
+            .collect()
+    }
+
+    #[test]
+    fn header_only_table_is_one_block() {
+        let blocks = split_table("Title\nName  Score\n", 2, MESSAGE_LIMIT);
+        assert_eq!(blocks, vec!["```\nTitle\nName  Score\n```"]);
+    }
+
+    #[test]
+    fn short_table_is_one_block() {
+        let blocks = split_table("Title\nName\na\nb\n", 2, MESSAGE_LIMIT);
+        assert_eq!(blocks, vec!["```\nTitle\nName\na\nb\n```"]);
+    }
+
+    #[test]
+    fn row_exactly_at_the_limit_fits() {
+        // "```\n" + "H\n" + row + "\n```" is 10 characters plus the row
+        let row = "r".repeat(10);
+        let blocks = split_table(&format!("H\n{}\n{}", row, row), 1, 20);
+        assert_eq!(blocks.len(), 2);
+        for block in &blocks {
+            assert_eq!(block.chars().count(), 20);
+            assert_eq!(block_lines(block), vec!["H", row.as_str()]);
+        }
+    }
+
+    #[test]
+    fn header_is_repeated_in_every_block() {
+        let rows: Vec<String> = (0..50)
+            .map(|index| format!("player {:02}", index))
+            .collect();
+        for header_lines in [1, 2] {
+            let header = ["Heading", "Name"][..header_lines].to_vec();
+            let table = header
+                .iter()
+                .map(|line| line.to_string())
+                .chain(rows.iter().cloned())
+                .collect::<Vec<String>>()
+                .join("\n");
+
+            let blocks = split_table(&table, header_lines, 100);
+            assert!(blocks.len() > 1);
+            let mut found = vec![];
+            for block in &blocks {
+                assert!(block.chars().count() <= 100);
+                let lines = block_lines(block);
+                assert_eq!(lines[..header_lines], header[..]);
+                assert!(lines.len() > header_lines);
+                found.extend(lines[header_lines..].iter().map(|line| line.to_string()));
+            }
+            assert_eq!(found, rows);
+        }
+    }
+
+    #[test]
+    fn row_longer_than_the_limit_is_kept() {
+        let row = "r".repeat(50);
+        let blocks = split_table(&format!("H\n{}", row), 1, 20);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(block_lines(&blocks[0]), vec!["H", row.as_str()]);
+    }
+}